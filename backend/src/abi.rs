@@ -0,0 +1,248 @@
+//! Abi generates the machine-readable JSON descriptors of actor interfaces and payloads, embedded
+//! in the actor's crate by the `#[fvm_actor]` and `#[fvm_payload]` macros.
+
+use std::collections::BTreeSet;
+use std::convert::TryInto;
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use serde_json::{json, Value};
+
+use crate::ast;
+use crate::ast::Mutability;
+use crate::payload::attrs::Codec as PayloadCodec;
+use crate::schema;
+use crate::utils::type_to_string;
+
+/// Describe the entry points of an actor: their name, number, mutability, arguments and return type.
+/// Payloads are referenced by name from the IPLD Schema of the values, and listed along with the
+/// methods, their layouts being described by their own `PAYLOAD_LAYOUT`.
+pub fn actor_interface(name: &str, entry_points: &[ast::ActorEntryPoint]) -> String {
+    let mut payloads = BTreeSet::new();
+    let methods: Vec<Value> = entry_points
+        .iter()
+        .map(|entry_point| {
+            let number: u64 = entry_point.binding.clone().try_into().unwrap();
+            // Injected arguments are not part of the parameters of the message
+            let arguments: Vec<Value> = entry_point
+                .arguments
+                .iter()
+                .filter(|argument| argument.is_payload())
+                .map(|argument| {
+                    let schema = type_schema(&argument.arg_type);
+                    payloads.extend(schema::referenced_types(&schema));
+                    json!({
+                        "name": argument.name,
+                        "type": type_to_string(&argument.arg_type),
+                        "schema": schema,
+                    })
+                })
+                .collect();
+            let returns = match &entry_point.return_type {
+                Some(return_type) if entry_point.returns => {
+                    let schema = type_schema(return_type);
+                    payloads.extend(schema::referenced_types(&schema));
+                    json!({
+                        "type": type_to_string(return_type),
+                        "schema": schema,
+                    })
+                }
+                _ => Value::Null,
+            };
+
+            json!({
+                "name": entry_point.name,
+                "number": number,
+                "mutability": mutability_name(&entry_point.mutability),
+                "constructor": entry_point.constructor,
+                "payable": entry_point.payable,
+                "nonreentrant": entry_point.nonreentrant,
+                "fallible": entry_point.fallible,
+                "arguments": arguments,
+                "returns": returns,
+            })
+        })
+        .collect();
+
+    json!({
+        "name": name,
+        "methods": methods,
+        "payloads": payloads,
+    })
+    .to_string()
+}
+
+/// Describe the layout of a payload: its codec and its fields in serialization order
+pub fn payload_layout(payload: &ast::PayloadStruct) -> String {
+    let fields: Vec<Value> = payload
+        .fields
+        .iter()
+        .map(|field| {
+            json!({
+                "name": field.name,
+                "type": type_to_string(&field.ty.to_token_stream()),
+            })
+        })
+        .collect();
+    let codec = match payload.codec {
+        PayloadCodec::DagCbor => "dag-cbor",
+    };
+
+    json!({
+        "name": payload.name,
+        "codec": codec,
+        // Payloads are encoded as tuples, fields are identified by their position
+        "encoding": "tuple",
+        "fields": fields,
+    })
+    .to_string()
+}
+
+// Render the IPLD Schema type of an argument or a return value
+fn type_schema(ty: &TokenStream) -> String {
+    match syn::parse2::<syn::Type>(ty.clone()) {
+        Ok(ty) => schema::value_schema(&ty),
+        Err(_) => String::from("Any"),
+    }
+}
+
+fn mutability_name(mutability: &Mutability) -> &'static str {
+    match mutability {
+        Mutability::Pure => "pure",
+        Mutability::View => "view",
+        Mutability::Write => "write",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::attrs::Method;
+    use quote::quote;
+
+    #[test]
+    fn describe_actor() {
+        let entry_points = vec![
+            ast::ActorEntryPoint {
+                rust_name: quote!(transfer),
+                name: String::from("transfer"),
+                binding: Method::Numeric(2),
+                mutability: Mutability::Write,
+                returns: true,
+                return_type: Some(quote!(Option<Receipt>)),
+                fallible: true,
+                constructor: false,
+                payable: true,
+                nonreentrant: true,
+                arguments: vec![
+                    ast::MethodArgument {
+                        name: String::from("params"),
+                        mutable: false,
+                        arg_type: quote!(TransferParams),
+                        kind: ast::ArgumentKind::Payload,
+                    },
+                    ast::MethodArgument {
+                        name: String::from("value"),
+                        mutable: false,
+                        arg_type: quote!(TokenAmount),
+                        kind: ast::ArgumentKind::Value,
+                    },
+                ],
+                caller_guards: vec![],
+            },
+            ast::ActorEntryPoint {
+                rust_name: quote!(balance),
+                name: String::from("balance"),
+                binding: Method::Numeric(3),
+                mutability: Mutability::View,
+                returns: true,
+                return_type: Some(quote!(Vec<u64>)),
+                fallible: false,
+                constructor: false,
+                payable: false,
+                nonreentrant: false,
+                arguments: vec![ast::MethodArgument {
+                    name: String::from("owner"),
+                    mutable: false,
+                    arg_type: quote!(Address),
+                    kind: ast::ArgumentKind::Payload,
+                }],
+                caller_guards: vec![],
+            },
+        ];
+
+        let interface: Value =
+            serde_json::from_str(&actor_interface("State", &entry_points)).unwrap();
+
+        assert_eq!(
+            interface,
+            json!({
+                "name": "State",
+                "methods": [{
+                    "name": "transfer",
+                    "number": 2,
+                    "mutability": "write",
+                    "constructor": false,
+                    "payable": true,
+                    "nonreentrant": true,
+                    "fallible": true,
+                    "arguments": [{
+                        "name": "params",
+                        "type": "TransferParams",
+                        "schema": "TransferParams",
+                    }],
+                    "returns": {"type": "Option<Receipt>", "schema": "nullable Receipt"},
+                }, {
+                    "name": "balance",
+                    "number": 3,
+                    "mutability": "view",
+                    "constructor": false,
+                    "payable": false,
+                    "nonreentrant": false,
+                    "fallible": false,
+                    "arguments": [{"name": "owner", "type": "Address", "schema": "Bytes"}],
+                    "returns": {"type": "Vec<u64>", "schema": "[Int]"},
+                }],
+                "payloads": ["Receipt", "TransferParams"],
+            })
+        );
+    }
+
+    #[test]
+    fn describe_payload() {
+        let payload = ast::PayloadStruct {
+            rust_name: quote!(TransferParams),
+            name: String::from("TransferParams"),
+            fields: vec![
+                ast::PayloadStructField {
+                    rust_name: quote!(to),
+                    name: String::from("to"),
+                    ty: syn::parse_quote!(Address),
+                    bytes: false,
+                },
+                ast::PayloadStructField {
+                    rust_name: quote!(amount),
+                    name: String::from("amount"),
+                    ty: syn::parse_quote!(Option<u64>),
+                    bytes: false,
+                },
+            ],
+            codec: PayloadCodec::DagCbor,
+        };
+
+        let layout: Value = serde_json::from_str(&payload_layout(&payload)).unwrap();
+
+        assert_eq!(
+            layout,
+            json!({
+                "name": "TransferParams",
+                "codec": "dag-cbor",
+                "encoding": "tuple",
+                "fields": [
+                    {"name": "to", "type": "Address"},
+                    {"name": "amount", "type": "Option<u64>"},
+                ],
+            })
+        );
+    }
+}
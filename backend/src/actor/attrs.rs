@@ -0,0 +1,85 @@
+//! Contains attributes available for the `#[fvm_actor]` procedural macro.
+
+use std::convert::TryFrom;
+
+use crate::utils::AnyIdent;
+use anyhow::Result;
+use syn::parse::{Parse, ParseStream, Result as SynResult};
+
+use crate::actor::error::Error::{
+    InvalidClientFeatureFormat, InvalidReentrancyFormat, UnknownAttribute, UnknownReentrancy,
+};
+
+#[derive(Clone, Debug)]
+pub enum ActorAttr {
+    Reentrancy(Reentrancy),
+    ClientFeature(String),
+}
+
+impl TryFrom<String> for ActorAttr {
+    type Error = crate::actor::error::Error;
+
+    fn try_from(attr: String) -> Result<Self, Self::Error> {
+        match attr.as_str() {
+            "reentrancy" => Ok(ActorAttr::Reentrancy(Reentrancy::default())),
+            "client_feature" => Ok(ActorAttr::ClientFeature(String::new())),
+            _ => Err(UnknownAttribute(attr)),
+        }
+    }
+}
+
+impl Parse for ActorAttr {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let original = input.fork();
+        let attr: AnyIdent = input.parse()?;
+        let attr = attr.0;
+
+        match ActorAttr::try_from(attr.to_string()) {
+            Ok(ActorAttr::Reentrancy(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                match input.parse::<syn::LitStr>() {
+                    Ok(reentrancy) => Ok(ActorAttr::Reentrancy(
+                        Reentrancy::try_from(reentrancy.value())
+                            .map_err(|err| original.error(format!("{}", err)))?,
+                    )),
+                    Err(err) => {
+                        Err(original.error(format!("{}", InvalidReentrancyFormat(err.to_string()))))
+                    }
+                }
+            }
+            Ok(ActorAttr::ClientFeature(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                // Expecting the name of a feature of the actor's crate, such as `"client"`
+                match input.parse::<syn::LitStr>() {
+                    Ok(feature) if !feature.value().is_empty() => {
+                        Ok(ActorAttr::ClientFeature(feature.value()))
+                    }
+                    _ => Err(original.error(format!("{}", InvalidClientFeatureFormat))),
+                }
+            }
+            Err(err) => Err(original.error(format!("{}", err))),
+        }
+    }
+}
+
+/// Whether the entry points of an actor can be called while one of them is being executed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Reentrancy {
+    /// Entry points can be re-entered, unless marked with `nonreentrant`
+    #[default]
+    Allow,
+    /// Entry points writing on the state can not be re-entered
+    Deny,
+}
+
+impl TryFrom<String> for Reentrancy {
+    type Error = crate::actor::error::Error;
+
+    fn try_from(reentrancy: String) -> Result<Self, Self::Error> {
+        match reentrancy.as_str() {
+            "allow" => Ok(Reentrancy::Allow),
+            "deny" => Ok(Reentrancy::Deny),
+            _ => Err(UnknownReentrancy(reentrancy)),
+        }
+    }
+}
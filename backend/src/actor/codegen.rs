@@ -1,69 +1,150 @@
 //! Codegen has the logic of code generation for our actor through the `#[fvm_state]` macro.
 
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
 use quote::{quote, ToTokens};
 use std::convert::TryInto;
 
+use crate::abi;
 use crate::ast;
 use crate::ast::Mutability;
+use crate::utils::type_to_string;
 
 impl ToTokens for ast::ActorImplementation {
     fn to_tokens(&self, into: &mut TokenStream) {
         let impl_member = self.rust_name.clone();
         let mut entry_points: Vec<TokenStream> = vec![];
+        let mut wrappers: Vec<TokenStream> = vec![];
+
+        // Only actors with non-reentrant entry points look through the lock when loading and saving
+        // their state, others read and write their state root as is
+        let (try_load, try_save) = if self
+            .entry_points
+            .iter()
+            .any(|entry_point| entry_point.nonreentrant)
+        {
+            (
+                quote!(fvm_rs_sdk::state::lock::try_load::<#impl_member>()),
+                quote!(fvm_rs_sdk::state::lock::try_save(&state)),
+            )
+        } else {
+            (quote!(#impl_member::try_load()), quote!(state.try_save()))
+        };
 
         for entry_point in self.entry_points.iter() {
             let entry_point_value: u64 = entry_point.binding.clone().try_into().unwrap();
             let method_name = entry_point.rust_name.clone();
 
-            // Token stream to represent variables in which we will deserialize
-            let mut parameters_variables = TokenStream::new();
-            // Token stream representing the parameters passed along the method
-            let mut method_parameters = TokenStream::new();
-            // Token stream representing the types of the variables, for deserialization
-            let mut parameters_types = TokenStream::new();
-            // Token stream representing the code to fetch & deserialize parameters
+            // Variables in which we will deserialize the parameters of the message
+            let mut parameters_variables: Vec<TokenStream> = vec![];
+            // Types of the variables, for deserialization
+            let mut parameters_types: Vec<TokenStream> = vec![];
+            // Variables passed along the method, in the order of its arguments
+            let mut method_parameters: Vec<TokenStream> = vec![];
+            // Token stream representing the code filling in injected arguments
+            let mut injected_arguments = TokenStream::new();
+            // Token stream representing the code to deserialize parameters
             let mut parameters_deserialization = TokenStream::new();
 
-            // If there are parameters for the method then prepare them for the call
-            if !entry_point.arguments.is_empty() {
-                for (i, argument) in entry_point.arguments.iter().enumerate() {
-                    let arg_type = argument.arg_type.clone();
-
-                    // Variable name based on argument name & index, to prevent naming collision
-                    let variable = syn::Member::Named(Ident::new(
-                        &format!("{}{}", argument.name, i),
-                        Span::call_site(),
-                    ));
-                    // Add variable type to token stream
-                    quote!(#arg_type).to_tokens(&mut parameters_types);
-
-                    // If argument has to be mutable pass variable name with `mut`
-                    if argument.mutable {
-                        quote!(mut #variable).to_tokens(&mut parameters_variables);
-                    } else {
-                        quote!(#variable).to_tokens(&mut parameters_variables);
-                    }
-                    // Pass variable name in method parameters
-                    quote!(#variable).to_tokens(&mut method_parameters);
-
-                    // If not the last entry, add comma
-                    if i != entry_point.arguments.len() - 1 {
-                        quote!(, ).to_tokens(&mut parameters_types);
-                        quote!(, ).to_tokens(&mut parameters_variables);
-                        quote!(, ).to_tokens(&mut method_parameters);
+            for (i, argument) in entry_point.arguments.iter().enumerate() {
+                let arg_type = argument.arg_type.clone();
+
+                // Variable name based on argument name & index, to prevent naming collision
+                let variable = Ident::new(&format!("{}{}", argument.name, i), Span::call_site());
+                // If argument has to be mutable bind variable with `mut`
+                let binding = if argument.mutable {
+                    quote!(mut #variable)
+                } else {
+                    quote!(#variable)
+                };
+                method_parameters.push(quote!(#variable));
+
+                match argument.kind {
+                    ast::ArgumentKind::Payload => {
+                        parameters_variables.push(binding);
+                        parameters_types.push(arg_type);
                     }
+                    ast::ArgumentKind::Value => quote!(
+                        let #binding: #arg_type = fvm_rs_sdk::syscall::message::value_received();
+                    )
+                    .to_tokens(&mut injected_arguments),
+                    ast::ArgumentKind::Context => quote!(
+                        let #binding: #arg_type = fvm_rs_sdk::actor::Context::current();
+                    )
+                    .to_tokens(&mut injected_arguments),
                 }
-                // Code to fetch bytes from pointer then deserialize in given variables
+            }
+
+            // If there are parameters for the method then prepare them for the call
+            let payload_arguments: Vec<&ast::MethodArgument> = entry_point
+                .arguments
+                .iter()
+                .filter(|argument| argument.is_payload())
+                .collect();
+            if !payload_arguments.is_empty() {
+                // Human readable representation of the expected parameters, for error messages
+                let expected_types = if payload_arguments.len() == 1 {
+                    type_to_string(&payload_arguments[0].arg_type)
+                } else {
+                    format!(
+                        "({})",
+                        payload_arguments
+                            .iter()
+                            .map(|argument| type_to_string(&argument.arg_type))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )
+                };
+                let missing_params_message = format!(
+                    "method '{}' expects parameters of type {} but none were provided",
+                    entry_point.name, expected_types
+                );
+                let deserialize_params_message = format!(
+                    "failed to deserialize parameters of method '{}', expected {}",
+                    entry_point.name, expected_types
+                );
+
+                // Code to deserialize the parameters in given variables
                 quote!(
-                    let params_bytes = fvm_rs_sdk::syscall::message::params_raw(params_pointer).unwrap().1;
-                    let (#parameters_variables): (#parameters_types) = fvm_rs_sdk::encoding::RawBytes::new(params_bytes).deserialize().unwrap();
+                    if params.is_empty() {
+                        return Err(fvm_rs_sdk::actor::ActorError::illegal_argument(#missing_params_message));
+                    }
+                    let (#(#parameters_variables),*): (#(#parameters_types),*) = match params.deserialize() {
+                        Ok(params) => params,
+                        Err(err) => return Err(fvm_rs_sdk::actor::ActorError::serialization(
+                            format!("{}: {}", #deserialize_params_message, err),
+                        )),
+                    };
                 )
                 .to_tokens(&mut parameters_deserialization);
             }
+            injected_arguments.to_tokens(&mut parameters_deserialization);
 
             let mut method_call = TokenStream::new();
 
+            // Value sent to entry points that are not payable is refused
+            if !entry_point.payable {
+                let not_payable_message = format!(
+                    "method '{}' is not payable but received a value of",
+                    entry_point.name
+                );
+                quote!(
+                    let value_received = fvm_rs_sdk::syscall::message::value_received();
+                    if value_received != fvm_rs_sdk::shared::econ::TokenAmount::default() {
+                        return Err(fvm_rs_sdk::actor::ActorError::illegal_argument(
+                            format!("{} {}", #not_payable_message, value_received),
+                        ));
+                    }
+                )
+                .to_tokens(&mut method_call);
+            }
+
+            // Guards on the caller type do not need the state, check them first
+            for guard in entry_point.caller_guards.iter() {
+                if let ast::CallerGuard::Types(_) = guard {
+                    caller_guard(&entry_point.name, guard).to_tokens(&mut method_call);
+                }
+            }
+
             // If method not pure load state
             if !matches!(entry_point.mutability, Mutability::Pure) {
                 // let keyword
@@ -72,78 +153,207 @@ impl ToTokens for ast::ActorImplementation {
                     // Add mut keyword if write mutability
                     quote!(mut).to_tokens(&mut method_call);
                 }
-                // Finalize load() call
+                // Finalize try_load() call
                 quote!(
-                    state = #impl_member::load();
+                    state = #try_load?;
                 )
                 .to_tokens(&mut method_call);
+
+                // Guards on the caller against state fields
+                for guard in entry_point.caller_guards.iter() {
+                    if !matches!(guard, ast::CallerGuard::Types(_)) {
+                        caller_guard(&entry_point.name, guard).to_tokens(&mut method_call);
+                    }
+                }
             }
 
-            // If method returns then store method result in variable
-            if entry_point.returns {
+            // Handle method calling based on mutability
+            let mut call = match entry_point.mutability {
+                Mutability::Pure => quote!(#impl_member::#method_name(#(#method_parameters),*)),
+                _ => quote!(state.#method_name(#(#method_parameters),*)),
+            };
+
+            // If method is fallible then return the error converted to an `ActorError`
+            if entry_point.fallible {
+                call = quote!(
+                    match #call {
+                        Ok(method_return) => method_return,
+                        Err(err) => return Err(err.into()),
+                    }
+                );
+            }
+
+            if entry_point.constructor {
+                // Constructor result is the initial state of the actor, only set by the init actor
                 quote!(
-                    let method_return =
+                    if fvm_rs_sdk::syscall::message::caller() != fvm_rs_sdk::actor::INIT_ACTOR_ID {
+                        return Err(fvm_rs_sdk::actor::ActorError::forbidden(
+                            "constructor can only be called by the init actor",
+                        ));
+                    }
+                    let state = #call;
+                    state.try_save()?;
                 )
                 .to_tokens(&mut method_call);
-            }
-
-            // Handle method calling based on mutability
-            match entry_point.mutability {
-                Mutability::Pure => quote!(
-                    #impl_member::#method_name(#method_parameters);
+            } else if entry_point.returns {
+                // If method returns then store method result in variable
+                quote!(
+                    let method_return = #call;
                 )
-                .to_tokens(&mut method_call),
-                _ => quote!(
-                    state.#method_name(#method_parameters);
+                .to_tokens(&mut method_call);
+            } else {
+                quote!(
+                    #call;
                 )
-                .to_tokens(&mut method_call),
-            };
+                .to_tokens(&mut method_call);
+            }
 
             // If method returns then convert result to bytes
             if entry_point.returns {
                 quote!(
-                    ret = match(fvm_rs_sdk::encoding::to_vec(&method_return)) {
+                    ret = match fvm_rs_sdk::encoding::to_vec(&method_return) {
                         Ok(ret) => Some(fvm_rs_sdk::encoding::RawBytes::new(ret)),
-                        Err(err) => {
-                            fvm_rs_sdk::syscall::vm::abort(
-                                fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
-                                Some(format!("failed to serialize return value: {:?}", err).as_str()),
-                            );
-                        }
+                        Err(err) => return Err(fvm_rs_sdk::actor::ActorError::serialization(
+                            format!("failed to serialize return value: {:?}", err),
+                        )),
                     };
-                ).to_tokens(&mut method_call)
+                )
+                .to_tokens(&mut method_call)
             }
 
-            // If mutability is write then save state
+            // If mutability is write then save state, only written if it was modified
             if matches!(entry_point.mutability, Mutability::Write) {
                 quote!(
-                    state.save();
+                    #try_save?;
                 )
                 .to_tokens(&mut method_call);
             }
 
+            let mut body = quote!(
+                let mut ret = None;
+                #method_call
+                Ok(ret)
+            );
+
+            // Non-reentrant entry points lock the state until it is saved. The lock is only
+            // released by the entry point holding it, whether it succeeds or fails, so that callers
+            // handling the error rather than aborting can call it again.
+            if entry_point.nonreentrant {
+                body = quote!(
+                    fvm_rs_sdk::state::lock::lock(<#impl_member as fvm_rs_sdk::state::StateObject>::HASH_CODE)?;
+                    #[allow(clippy::redundant_closure_call)]
+                    let result = (|| -> Result<Option<fvm_rs_sdk::encoding::RawBytes>, fvm_rs_sdk::actor::ActorError> {
+                        #body
+                    })();
+                    let unlocked = fvm_rs_sdk::state::lock::unlock();
+                    let ret = result?;
+                    unlocked?;
+                    Ok(ret)
+                );
+            }
+
             entry_points.push(quote!(
                 #entry_point_value => {
                     #parameters_deserialization
-                    let mut ret = None;
-                    #method_call
-                    ret
+                    #body
                 }
             ));
+
+            let (wrapper_parameters, params_serialization) =
+                typed_parameters(entry_point, &impl_member);
+            let wrapper = match &entry_point.return_type {
+                Some(return_type) if entry_point.returns => {
+                    let return_type = replace_self(return_type.clone(), &impl_member);
+                    quote!(
+                        pub fn #method_name(#wrapper_parameters) -> Result<#return_type, fvm_rs_sdk::actor::ActorError> {
+                            let params = #params_serialization;
+                            match super::dispatch(#entry_point_value, &params)? {
+                                Some(ret) => ret.deserialize().map_err(|err| {
+                                    fvm_rs_sdk::actor::ActorError::serialization(
+                                        format!("failed to deserialize return value: {}", err),
+                                    )
+                                }),
+                                None => Err(fvm_rs_sdk::actor::ActorError::serialization(
+                                    "expected a return value but none was returned",
+                                )),
+                            }
+                        }
+                    )
+                }
+                _ => quote!(
+                    pub fn #method_name(#wrapper_parameters) -> Result<(), fvm_rs_sdk::actor::ActorError> {
+                        let params = #params_serialization;
+                        super::dispatch(#entry_point_value, &params)?;
+                        Ok(())
+                    }
+                ),
+            };
+            wrappers.push(wrapper);
         }
 
+        let interface = abi::actor_interface(&self.name, &self.entry_points);
+        let client = client(&client_name(&impl_member), &self.entry_points, &impl_member);
+
+        // Actors calling this actor through its client depend on its crate with the feature set
+        // by `client_feature`, which drops the export so that it does not clash with their own
+        let export_guard = self
+            .client_feature
+            .as_ref()
+            .map(|feature| quote!(#[cfg(not(feature = #feature))]));
+
         quote!(
+            /// JSON descriptor of the actor interface, listing its entry points along with their
+            /// method number, mutability, arguments and return type.
+            pub const ACTOR_INTERFACE: &str = #interface;
+
+            /// Dispatch a call to the entry point bound to the method number, returning the
+            /// serialized return data or the error the call failed with.
+            pub fn dispatch(
+                method: u64,
+                params: &fvm_rs_sdk::encoding::RawBytes,
+            ) -> Result<Option<fvm_rs_sdk::encoding::RawBytes>, fvm_rs_sdk::actor::ActorError> {
+                use fvm_rs_sdk::state::StateObject;
+
+                match method {
+                    #(#entry_points),*
+                    _ => Err(fvm_rs_sdk::actor::ActorError::new(
+                        fvm_rs_sdk::shared::error::ExitCode::USR_UNHANDLED_MESSAGE,
+                        "unrecognized method",
+                    )),
+                }
+            }
+
+            /// Typed wrappers calling each entry point through `dispatch`.
+            pub mod dispatch {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#wrappers)*
+            }
+
+            #client
+
+            #export_guard
             #[no_mangle]
             pub fn invoke(params_pointer: u32) -> u32 {
-                // Conduct method dispatch. Handle input parameters and return data.
-                let ret: Option<fvm_rs_sdk::encoding::RawBytes> =
-                    match fvm_rs_sdk::syscall::message::method_number() {
-                        #(#entry_points),*
-                        _ => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_UNHANDLED_MESSAGE.value(),
-                            Some("unrecognized method"),
+                // Fetch input parameters, if any
+                let params = if params_pointer == fvm_rs_sdk::syscall::NO_DATA_BLOCK_ID {
+                    fvm_rs_sdk::encoding::RawBytes::default()
+                } else {
+                    match fvm_rs_sdk::syscall::message::params_raw(params_pointer) {
+                        Ok((_, params_bytes)) => fvm_rs_sdk::encoding::RawBytes::new(params_bytes),
+                        Err(err) => fvm_rs_sdk::syscall::vm::abort(
+                            fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
+                            Some(format!("failed to read parameters: {:?}", err).as_str()),
                         ),
-                    };
+                    }
+                };
+
+                // Conduct method dispatch, aborting with the exit code of the error if any
+                let ret = match dispatch(fvm_rs_sdk::syscall::message::method_number(), &params) {
+                    Ok(ret) => ret,
+                    Err(err) => fvm_rs_sdk::syscall::vm::abort(err.exit_code().value(), Some(err.msg())),
+                };
 
                 match ret {
                     None => fvm_rs_sdk::syscall::NO_DATA_BLOCK_ID,
@@ -164,5 +374,216 @@ impl ToTokens for ast::ActorImplementation {
     }
 }
 
-#[cfg(test)]
-mod tests {}
+// Code aborting with `USR_FORBIDDEN` if the caller of the entry point does not pass the guard
+fn caller_guard(entry_point_name: &str, guard: &ast::CallerGuard) -> TokenStream {
+    let (condition, message) = match guard {
+        ast::CallerGuard::Field(field) => (
+            quote!(fvm_rs_sdk::actor::Caller::is_caller(
+                &state.#field,
+                fvm_rs_sdk::syscall::message::caller(),
+            )),
+            format!(
+                "method '{}' can only be called by '{}'",
+                entry_point_name, field
+            ),
+        ),
+        ast::CallerGuard::InField(field) => (
+            quote!((&state.#field).into_iter().any(|allowed| {
+                fvm_rs_sdk::actor::Caller::is_caller(
+                    allowed,
+                    fvm_rs_sdk::syscall::message::caller(),
+                )
+            })),
+            format!(
+                "method '{}' can only be called by an actor in '{}'",
+                entry_point_name, field
+            ),
+        ),
+        ast::CallerGuard::Types(caller_types) => {
+            let types = caller_types.iter().map(|caller_type| {
+                let variant = Ident::new(&format!("{:?}", caller_type), Span::call_site());
+                quote!(fvm_rs_sdk::shared::actor::builtin::Type::#variant)
+            });
+            (
+                quote!(fvm_rs_sdk::actor::caller_is_type(&[#(#types),*])),
+                format!(
+                    "method '{}' can only be called by actors of type {}",
+                    entry_point_name,
+                    caller_types
+                        .iter()
+                        .map(|caller_type| caller_type.name())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ),
+            )
+        }
+    };
+
+    quote!(
+        if !#condition {
+            return Err(fvm_rs_sdk::actor::ActorError::forbidden(#message));
+        }
+    )
+}
+
+// Name of the client generated for an implementation type (e.g. `TokenState` => `TokenStateClient`)
+fn client_name(impl_member: &TokenStream) -> Ident {
+    let name = match impl_member.clone().into_iter().last() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => String::from("Actor"),
+    };
+    Ident::new(&format!("{}Client", name), Span::call_site())
+}
+
+// Generate a client calling the entry points of a deployed actor through the `send` syscall,
+// constructors being reserved to the init actor
+pub(crate) fn client(
+    client_name: &Ident,
+    entry_points: &[ast::ActorEntryPoint],
+    impl_member: &TokenStream,
+) -> TokenStream {
+    let methods = entry_points
+        .iter()
+        .filter(|entry_point| !entry_point.constructor)
+        .map(|entry_point| {
+            let method_name = entry_point.rust_name.clone();
+            let entry_point_value: u64 = entry_point.binding.clone().try_into().unwrap();
+            let (mut parameters, params_serialization) = typed_parameters(entry_point, impl_member);
+            // Payable entry points are called with the value to send along the message
+            let value = if entry_point.payable {
+                if !parameters.is_empty() {
+                    quote!(, ).to_tokens(&mut parameters);
+                }
+                quote!(value: fvm_rs_sdk::shared::econ::TokenAmount).to_tokens(&mut parameters);
+                quote!(value)
+            } else {
+                quote!(fvm_rs_sdk::shared::econ::TokenAmount::default())
+            };
+            let send_failed_message = format!("failed to call method '{}'", entry_point.name);
+            let call_failed_message = format!("method '{}' failed", entry_point.name);
+
+            let send = quote!(
+                let params = #params_serialization;
+                let receipt = match fvm_rs_sdk::syscall::send::send(
+                    &self.address,
+                    #entry_point_value,
+                    params,
+                    #value,
+                ) {
+                    Ok(receipt) => receipt,
+                    Err(err) => return Err(fvm_rs_sdk::actor::ActorError::unspecified(
+                        format!("{}: {:?}", #send_failed_message, err),
+                    )),
+                };
+                // Propagate the exit code of the called actor
+                if !receipt.exit_code.is_success() {
+                    return Err(fvm_rs_sdk::actor::ActorError::new(
+                        receipt.exit_code,
+                        #call_failed_message,
+                    ));
+                }
+            );
+
+            match &entry_point.return_type {
+                Some(return_type) if entry_point.returns => {
+                    let return_type = replace_self(return_type.clone(), impl_member);
+                    quote!(
+                        pub fn #method_name(&self, #parameters) -> Result<#return_type, fvm_rs_sdk::actor::ActorError> {
+                            #send
+                            receipt.return_data.deserialize().map_err(|err| {
+                                fvm_rs_sdk::actor::ActorError::serialization(
+                                    format!("failed to deserialize return value: {}", err),
+                                )
+                            })
+                        }
+                    )
+                }
+                _ => quote!(
+                    pub fn #method_name(&self, #parameters) -> Result<(), fvm_rs_sdk::actor::ActorError> {
+                        #send
+                        Ok(())
+                    }
+                ),
+            }
+        });
+
+    quote!(
+        /// Client calling the entry points of a deployed actor through the `send` syscall.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct #client_name {
+            /// Address of the called actor
+            pub address: fvm_rs_sdk::shared::address::Address,
+        }
+
+        impl #client_name {
+            /// Create a client for the actor at the given address
+            pub fn new(address: fvm_rs_sdk::shared::address::Address) -> Self {
+                Self { address }
+            }
+
+            #(#methods)*
+        }
+    )
+}
+
+// Typed parameters of an entry point used outside of its implementation, along with the code
+// serializing them as the entry point expects them
+fn typed_parameters(
+    entry_point: &ast::ActorEntryPoint,
+    impl_member: &TokenStream,
+) -> (TokenStream, TokenStream) {
+    let mut parameters: Vec<TokenStream> = vec![];
+    let mut variables: Vec<TokenStream> = vec![];
+
+    // Injected arguments are filled in by the called actor
+    for (i, argument) in entry_point.arguments.iter().enumerate() {
+        if !argument.is_payload() {
+            continue;
+        }
+        // Variable name based on argument name & index, to prevent naming collision
+        let variable = Ident::new(&format!("{}{}", argument.name, i), Span::call_site());
+        // `Self` refers to the implementation type
+        let arg_type = replace_self(argument.arg_type.clone(), impl_member);
+
+        parameters.push(quote!(#variable: #arg_type));
+        variables.push(quote!(#variable));
+    }
+
+    let params_serialization = match variables.len() {
+        0 => quote!(fvm_rs_sdk::encoding::RawBytes::default()),
+        len => {
+            let params = if len == 1 {
+                quote!(#(#variables),*)
+            } else {
+                quote!((#(#variables),*))
+            };
+            quote!(
+                match fvm_rs_sdk::encoding::RawBytes::serialize(#params) {
+                    Ok(params) => params,
+                    Err(err) => return Err(fvm_rs_sdk::actor::ActorError::serialization(
+                        format!("failed to serialize parameters: {}", err),
+                    )),
+                }
+            )
+        }
+    };
+
+    (quote!(#(#parameters),*), params_serialization)
+}
+
+// Replace `Self` by the implementation type, for types used outside of the implementation
+fn replace_self(tokens: TokenStream, impl_member: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => impl_member.clone(),
+            TokenTree::Group(group) => {
+                let mut replaced =
+                    Group::new(group.delimiter(), replace_self(group.stream(), impl_member));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
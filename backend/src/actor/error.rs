@@ -0,0 +1,16 @@
+#[derive(thiserror::Error, Debug)]
+/// Errors related to an actor's implementation.
+pub enum Error {
+    /// This error is thrown when the specified attribute is not handled
+    #[error("unknown attribute '{0}'")]
+    UnknownAttribute(String),
+    /// This error is thrown when the reentrancy is not a literal string
+    #[error("invalid reentrancy format, {0}")]
+    InvalidReentrancyFormat(String),
+    /// This error is thrown when the reentrancy is not one we can handle
+    #[error("unknown reentrancy '{0}', expected 'allow' or 'deny'")]
+    UnknownReentrancy(String),
+    /// This error is thrown when the client feature is not a literal string naming a feature
+    #[error("invalid client feature format, expected the name of a feature as a string literal")]
+    InvalidClientFeatureFormat,
+}
@@ -1,13 +1,15 @@
 //! Contains all structures that can be parsed from a `TokenStream`. They will be used when generating
 //! code
 
-use crate::export::attrs::Method;
+use crate::export::attrs::{CallerType, Method};
 use proc_macro2::{Ident, TokenStream};
 use quote::ToTokens;
 use syn;
 
 use crate::payload::attrs::Codec as PayloadCodec;
 use crate::state::attrs::Codec as StateCodec;
+use crate::state::attrs::HashFunction as StateHashFunction;
+use crate::state::attrs::Version as StateVersion;
 use crate::{Diagnostic, TryToTokens};
 
 /// An abstract syntax tree representing a rust program.
@@ -18,8 +20,14 @@ pub struct Program {
     pub state_structs: Vec<StateStruct>,
     /// Actor implementation
     pub actor_implementation: Option<ActorImplementation>,
+    /// Actor interface declared on a trait
+    pub actor_interface: Option<ActorInterface>,
     /// state rust structs
     pub payload_structs: Vec<PayloadStruct>,
+    /// event rust structs
+    pub event_structs: Vec<EventStruct>,
+    /// Migration hooks upgrading older layouts of a state
+    pub migration_hooks: Vec<MigrationHook>,
 }
 
 impl TryToTokens for Program {
@@ -33,11 +41,23 @@ impl TryToTokens for Program {
         if let Some(actor_implementation) = &self.actor_implementation {
             actor_implementation.to_tokens(into);
         }
+        // Handling tagged trait
+        if let Some(actor_interface) = &self.actor_interface {
+            actor_interface.to_tokens(into);
+        }
 
         for s in self.payload_structs.iter() {
             s.to_tokens(into);
         }
 
+        for s in self.event_structs.iter() {
+            s.to_tokens(into);
+        }
+
+        for hook in self.migration_hooks.iter() {
+            hook.to_tokens(into);
+        }
+
         Ok(())
     }
 }
@@ -52,8 +72,14 @@ pub struct StateStruct {
     pub name: String,
     /// All the fields of this struct to export
     pub fields: Vec<StateStructField>,
+    /// All the fields of this struct, in their serialization order, whatever their visibility
+    pub serialized_fields: Vec<StateStructField>,
     /// Codec used to store state
     pub codec: StateCodec,
+    /// Hash function used to compute the CID of the state
+    pub hash: StateHashFunction,
+    /// Version of the layout of the state, stored along with it when specified
+    pub version: Option<StateVersion>,
 }
 
 /// The field of a struct
@@ -68,6 +94,12 @@ pub struct StateStructField {
     pub struct_name: Ident,
     /// The type of this field
     pub ty: syn::Type,
+    /// Boolean to know if the field is stored in its own block, loaded on first access
+    pub lazy: bool,
+    /// Boolean to know if the field holds a collection of the SDK, stored in its own blocks
+    pub collection: bool,
+    /// Boolean to know if a serde attribute serializes the field as bytes
+    pub bytes: bool,
 }
 
 /// Information about an Implementation being used as an actor interface
@@ -80,6 +112,22 @@ pub struct ActorImplementation {
     pub name: String,
     /// The entry points that are available for the actor
     pub entry_points: Vec<ActorEntryPoint>,
+    /// Feature of the actor's crate leaving out its `invoke` export, if any
+    pub client_feature: Option<String>,
+}
+
+/// Information about a trait declaring the entry points of actors implementing it
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct ActorInterface {
+    /// The name of the trait in Rust code
+    pub rust_name: Ident,
+    /// The name of the trait in code
+    pub name: String,
+    /// The entry points declared by the trait
+    pub entry_points: Vec<ActorEntryPoint>,
+    /// The trait definition, passed along to the implementations to bind their entry points
+    pub definition: TokenStream,
 }
 
 /// Information about an entry point being used in an actor
@@ -96,8 +144,32 @@ pub struct ActorEntryPoint {
     pub mutability: Mutability,
     /// Boolean to know if entry point return data
     pub returns: bool,
+    /// Type of the data returned by the entry point, unwrapped from its `Result` if fallible
+    pub return_type: Option<TokenStream>,
+    /// Boolean to know if entry point returns a `Result` whose error aborts the execution
+    pub fallible: bool,
+    /// Boolean to know if the entry point constructs the actor's initial state
+    pub constructor: bool,
+    /// Boolean to know if the entry point accepts value sent along the message
+    pub payable: bool,
+    /// Boolean to know if the entry point locks the state against re-entrant calls while executed
+    pub nonreentrant: bool,
     /// Arguments expected by the method
     pub arguments: Vec<MethodArgument>,
+    /// Guards on the caller, checked before invoking the method
+    pub caller_guards: Vec<CallerGuard>,
+}
+
+/// Information about a guard restricting the callers of an entry point
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub enum CallerGuard {
+    // Caller should be the one stored in the given state field
+    Field(Ident),
+    // Caller should be one of the elements stored in the given state field
+    InField(Ident),
+    // Caller should be a builtin actor of one of the given types
+    Types(Vec<CallerType>),
 }
 
 /// Information about the mutability of an entry point
@@ -122,6 +194,27 @@ pub struct MethodArgument {
     pub mutable: bool,
     /// The internal entry point value specified for the method
     pub arg_type: TokenStream,
+    /// Where the value of the argument comes from when the entry point is invoked
+    pub kind: ArgumentKind,
+}
+
+/// Information about the source of an argument for a method used as an entry point
+#[cfg_attr(feature = "extra-traits", derive(Debug, PartialEq, Eq))]
+#[derive(Clone)]
+pub enum ArgumentKind {
+    // Deserialized from the parameters of the message
+    Payload,
+    // Value received with the message, for arguments marked with `#[value]`
+    Value,
+    // Context of the message, for arguments of type `fvm_rs_sdk::actor::Context`
+    Context,
+}
+
+impl MethodArgument {
+    /// Returns true if the argument is deserialized from the parameters of the message
+    pub fn is_payload(&self) -> bool {
+        matches!(self.kind, ArgumentKind::Payload)
+    }
 }
 
 /// Information about a Struct being used as a payload object
@@ -132,6 +225,60 @@ pub struct PayloadStruct {
     pub rust_name: TokenStream,
     /// The name of the struct for the SDK
     pub name: String,
+    /// All the fields of this struct, in their serialization order
+    pub fields: Vec<PayloadStructField>,
     /// Codec used for the payload
     pub codec: PayloadCodec,
 }
+
+/// The field of a payload struct
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct PayloadStructField {
+    /// The name of the field in Rust code
+    pub rust_name: TokenStream,
+    /// The name of the field in code
+    pub name: String,
+    /// The type of this field
+    pub ty: syn::Type,
+    /// Boolean to know if a serde attribute serializes the field as bytes
+    pub bytes: bool,
+}
+
+/// Information about a Struct being used as an event
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct EventStruct {
+    /// The name of the struct in Rust code
+    pub rust_name: TokenStream,
+    /// The name of the event, as published in its entries
+    pub name: String,
+    /// All the fields of this struct, each published as an entry of the event
+    pub fields: Vec<EventStructField>,
+}
+
+/// The field of an event struct
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct EventStructField {
+    /// The name of the field in Rust code
+    pub rust_name: TokenStream,
+    /// The name of the field, used as the key of its entry
+    pub name: String,
+    /// Boolean to know if the entry should be indexed
+    pub indexed: bool,
+}
+
+/// Information about a function migrating a layout of a state to the next one
+#[cfg_attr(feature = "extra-traits", derive(Debug))]
+#[derive(Clone)]
+pub struct MigrationHook {
+    /// The name of the function in Rust code
+    pub rust_name: Ident,
+    /// Version of the layout taken by the function
+    pub from: u64,
+    /// Type of the layout taken by the function
+    pub previous: syn::Type,
+    /// Type of the layout returned by the function
+    pub next: syn::Type,
+}
@@ -0,0 +1,49 @@
+//! Contains attributes available for the `#[fvm_event]` procedural macro.
+
+use std::convert::TryFrom;
+
+use crate::utils::AnyIdent;
+use anyhow::Result;
+use syn::parse::{Parse, ParseStream, Result as SynResult};
+
+use crate::event::error::Error::{EmptyName, InvalidNameFormat, UnknownAttribute};
+
+#[derive(Clone, Debug)]
+pub enum EventAttr {
+    Name(String),
+}
+
+impl TryFrom<String> for EventAttr {
+    type Error = crate::event::error::Error;
+
+    fn try_from(attr: String) -> Result<Self, Self::Error> {
+        match attr.as_str() {
+            "name" => Ok(EventAttr::Name(String::new())),
+            _ => Err(UnknownAttribute(attr)),
+        }
+    }
+}
+
+impl Parse for EventAttr {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let original = input.fork();
+        let attr: AnyIdent = input.parse()?;
+        let attr = attr.0;
+
+        match EventAttr::try_from(attr.to_string()) {
+            Ok(EventAttr::Name(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                match input.parse::<syn::LitStr>() {
+                    Ok(name) if name.value().is_empty() => {
+                        Err(original.error(format!("{}", EmptyName)))
+                    }
+                    Ok(name) => Ok(EventAttr::Name(name.value())),
+                    Err(err) => {
+                        Err(original.error(format!("{}", InvalidNameFormat(err.to_string()))))
+                    }
+                }
+            }
+            Err(err) => Err(original.error(format!("{}", err))),
+        }
+    }
+}
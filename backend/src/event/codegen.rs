@@ -0,0 +1,123 @@
+//! Codegen has the logic of code generation for our actor through the `#[fvm_event]` macro.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::ast;
+
+impl ToTokens for ast::EventStruct {
+    fn to_tokens(&self, into: &mut TokenStream) {
+        let rust_name = self.rust_name.clone();
+        let name = self.name.clone();
+
+        // Each field is published as an entry keyed by its name, after the entry holding the type
+        let entries: Vec<TokenStream> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_name = field.rust_name.clone();
+                let key = field.name.clone();
+                let flags = if field.indexed {
+                    quote!(fvm_rs_sdk::event::FLAG_INDEXED_ALL)
+                } else {
+                    quote!(fvm_rs_sdk::event::FLAG_INDEXED_NONE)
+                };
+                quote!(fvm_rs_sdk::event::Entry::new(#flags, #key, &self.#field_name)?)
+            })
+            .collect();
+
+        *into = (quote! {
+            #into
+
+            impl fvm_rs_sdk::event::Event for #rust_name {
+                const NAME: &'static str = #name;
+
+                fn to_actor_event(&self) -> Result<fvm_rs_sdk::event::ActorEvent, fvm_rs_sdk::event::error::Error> {
+                    Ok(fvm_rs_sdk::event::ActorEvent {
+                        entries: vec![
+                            fvm_rs_sdk::event::Entry::new(
+                                fvm_rs_sdk::event::FLAG_INDEXED_ALL,
+                                fvm_rs_sdk::event::EVENT_TYPE_KEY,
+                                Self::NAME,
+                            )?,
+                            #(#entries),*
+                        ],
+                    })
+                }
+            }
+        })
+        .to_token_stream();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TryToTokens;
+
+    #[test]
+    fn basic_struct() {
+        let mut expected_final_stream = TokenStream::new();
+
+        (quote! {
+            pub struct Transfer {
+                pub to: u64,
+                pub amount: u64
+            }
+
+            impl fvm_rs_sdk::event::Event for Transfer {
+                const NAME: &'static str = "Transfer";
+
+                fn to_actor_event(&self) -> Result<fvm_rs_sdk::event::ActorEvent, fvm_rs_sdk::event::error::Error> {
+                    Ok(fvm_rs_sdk::event::ActorEvent {
+                        entries: vec![
+                            fvm_rs_sdk::event::Entry::new(
+                                fvm_rs_sdk::event::FLAG_INDEXED_ALL,
+                                fvm_rs_sdk::event::EVENT_TYPE_KEY,
+                                Self::NAME,
+                            )?,
+                            fvm_rs_sdk::event::Entry::new(fvm_rs_sdk::event::FLAG_INDEXED_ALL, "to", &self.to)?,
+                            fvm_rs_sdk::event::Entry::new(fvm_rs_sdk::event::FLAG_INDEXED_NONE, "amount", &self.amount)?
+                        ],
+                    })
+                }
+            }
+        })
+        .to_tokens(&mut expected_final_stream);
+
+        let mut token_stream = TokenStream::new();
+
+        (quote! {
+            pub struct Transfer {
+                pub to: u64,
+                pub amount: u64
+            }
+        })
+        .to_tokens(&mut token_stream);
+
+        let event = ast::EventStruct {
+            rust_name: quote!(Transfer),
+            name: String::from("Transfer"),
+            fields: vec![
+                ast::EventStructField {
+                    rust_name: quote!(to),
+                    name: String::from("to"),
+                    indexed: true,
+                },
+                ast::EventStructField {
+                    rust_name: quote!(amount),
+                    name: String::from("amount"),
+                    indexed: false,
+                },
+            ],
+        };
+        let program = ast::Program {
+            event_structs: vec![event],
+            ..Default::default()
+        };
+
+        program.try_to_tokens(&mut token_stream).unwrap();
+
+        assert_eq!(token_stream.to_string(), expected_final_stream.to_string());
+    }
+}
@@ -0,0 +1,13 @@
+#[derive(thiserror::Error, Debug)]
+/// Errors related to an actor's events.
+pub enum Error {
+    /// This error is thrown when the specified attribute is not handled
+    #[error("unknown attribute '{0}'")]
+    UnknownAttribute(String),
+    /// This error is thrown when the name is not a literal string
+    #[error("invalid name format, {0}")]
+    InvalidNameFormat(String),
+    /// This error is thrown when the name is an empty string
+    #[error("event name can not be empty")]
+    EmptyName,
+}
@@ -0,0 +1,3 @@
+pub mod attrs;
+pub mod codegen;
+mod error;
@@ -4,13 +4,26 @@ use std::convert::{TryFrom, TryInto};
 
 use crate::utils::AnyIdent;
 use anyhow::Result;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::ToTokens;
 use syn::parse::{Parse, ParseStream, Result as SynResult};
 
-use crate::export::error::Error::{InvalidMethodNumValue, InvalidNumericValue, UnknownAttribute};
+use crate::export::error::Error::{
+    EmptyCallerTypes, EmptyMethodName, InvalidMethodNameValue, InvalidMethodNumValue,
+    InvalidNumericValue, InvalidStateField, UnknownAttribute, UnknownCallerType,
+};
+use crate::export::frc42::{method_hash, CONSTRUCTOR_METHOD_NUMBER};
 
 #[derive(Clone, Debug)]
 pub enum ExportAttr {
-    BindingMethod(Method),
+    BindingMethod(Binding),
+    AllowReserved(bool),
+    Constructor(bool),
+    Payable(bool),
+    NonReentrant(bool),
+    Caller(Ident),
+    CallerIn(Ident),
+    CallerType(Vec<CallerType>),
 }
 
 impl TryFrom<String> for ExportAttr {
@@ -18,7 +31,21 @@ impl TryFrom<String> for ExportAttr {
 
     fn try_from(attr: String) -> Result<Self, Self::Error> {
         match attr.as_str() {
-            "method_num" => Ok(ExportAttr::BindingMethod(Method::default())),
+            "method_num" => Ok(ExportAttr::BindingMethod(Binding::default())),
+            "method_name" => Ok(ExportAttr::BindingMethod(Binding {
+                method: Method::Name(String::new()),
+                literal: None,
+            })),
+            "allow_reserved" => Ok(ExportAttr::AllowReserved(true)),
+            "constructor" => Ok(ExportAttr::Constructor(true)),
+            "payable" => Ok(ExportAttr::Payable(true)),
+            "nonreentrant" => Ok(ExportAttr::NonReentrant(true)),
+            "caller" => Ok(ExportAttr::Caller(Ident::new("caller", Span::call_site()))),
+            "caller_in" => Ok(ExportAttr::CallerIn(Ident::new(
+                "caller_in",
+                Span::call_site(),
+            ))),
+            "caller_type" => Ok(ExportAttr::CallerType(vec![])),
             _ => Err(UnknownAttribute(attr)),
         }
     }
@@ -31,26 +58,182 @@ impl Parse for ExportAttr {
         let attr = attr.0;
 
         match ExportAttr::try_from(attr.to_string()) {
-            Ok(ExportAttr::BindingMethod(Method::Numeric(_))) => {
+            Ok(ExportAttr::BindingMethod(Binding {
+                method: Method::Numeric(_),
+                ..
+            })) => {
                 input.parse::<syn::token::Eq>()?;
                 // Try to get value from parsing an integer
                 if let Ok(num) = input.parse::<syn::LitInt>() {
-                    return Ok(ExportAttr::BindingMethod(Method::Numeric(
-                        num.base10_parse::<u64>().map_err(|_| {
+                    return Ok(ExportAttr::BindingMethod(Binding {
+                        method: Method::Numeric(num.base10_parse::<u64>().map_err(|_| {
                             original.error(format!("{}", InvalidNumericValue(num.to_string())))
-                        })?,
-                    )));
+                        })?),
+                        literal: Some(num.to_token_stream()),
+                    }));
                 }
                 Err(original.error(format!("{}", InvalidMethodNumValue)))
             }
+            Ok(ExportAttr::BindingMethod(Binding {
+                method: Method::Name(_),
+                ..
+            })) => {
+                // Without value, the method name is derived from the method identifier
+                if !input.peek(syn::token::Eq) {
+                    return Ok(ExportAttr::BindingMethod(Binding {
+                        method: Method::Name(String::new()),
+                        literal: None,
+                    }));
+                }
+                input.parse::<syn::token::Eq>()?;
+                // Try to get value from parsing a string
+                if let Ok(name) = input.parse::<syn::LitStr>() {
+                    if name.value().is_empty() {
+                        return Err(original.error(format!("{}", EmptyMethodName)));
+                    }
+                    return Ok(ExportAttr::BindingMethod(Binding {
+                        method: Method::Name(name.value()),
+                        literal: Some(name.to_token_stream()),
+                    }));
+                }
+                Err(original.error(format!("{}", InvalidMethodNameValue)))
+            }
+            Ok(ExportAttr::AllowReserved(value)) => Ok(ExportAttr::AllowReserved(value)),
+            Ok(ExportAttr::Constructor(value)) => Ok(ExportAttr::Constructor(value)),
+            Ok(ExportAttr::Payable(value)) => Ok(ExportAttr::Payable(value)),
+            Ok(ExportAttr::NonReentrant(value)) => Ok(ExportAttr::NonReentrant(value)),
+            Ok(ExportAttr::Caller(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                Ok(ExportAttr::Caller(parse_state_field(
+                    input, &original, "caller",
+                )?))
+            }
+            Ok(ExportAttr::CallerIn(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                Ok(ExportAttr::CallerIn(parse_state_field(
+                    input,
+                    &original,
+                    "caller_in",
+                )?))
+            }
+            Ok(ExportAttr::CallerType(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                // Expecting a list of actor types, such as `[account, multisig]`
+                let content;
+                syn::bracketed!(content in input);
+                let types =
+                    syn::punctuated::Punctuated::<AnyIdent, syn::token::Comma>::parse_terminated(
+                        &content,
+                    )?;
+                if types.is_empty() {
+                    return Err(original.error(format!("{}", EmptyCallerTypes)));
+                }
+                let mut caller_types = vec![];
+                for caller_type in types {
+                    caller_types.push(
+                        CallerType::try_from(caller_type.0.to_string())
+                            .map_err(|err| original.error(format!("{}", err)))?,
+                    );
+                }
+                Ok(ExportAttr::CallerType(caller_types))
+            }
             Err(err) => Err(original.error(format!("{}", err))),
         }
     }
 }
 
+// Parse the name of a state field given as a literal string
+fn parse_state_field(input: ParseStream, original: ParseStream, attr: &str) -> SynResult<Ident> {
+    match input
+        .parse::<syn::LitStr>()
+        .map(|field| field.parse::<Ident>())
+    {
+        Ok(Ok(field)) => Ok(field),
+        _ => Err(original.error(format!("{}", InvalidStateField(attr.to_string())))),
+    }
+}
+
+/// Builtin actor types that can be required from the caller of an entry point
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CallerType {
+    System,
+    Init,
+    Cron,
+    Account,
+    Power,
+    Miner,
+    Market,
+    PaymentChannel,
+    Multisig,
+    Reward,
+    VerifiedRegistry,
+}
+
+impl CallerType {
+    /// Name of the actor type, as used by the Filecoin builtin actors
+    pub fn name(&self) -> &'static str {
+        match self {
+            CallerType::System => "system",
+            CallerType::Init => "init",
+            CallerType::Cron => "cron",
+            CallerType::Account => "account",
+            CallerType::Power => "storagepower",
+            CallerType::Miner => "storageminer",
+            CallerType::Market => "storagemarket",
+            CallerType::PaymentChannel => "paymentchannel",
+            CallerType::Multisig => "multisig",
+            CallerType::Reward => "reward",
+            CallerType::VerifiedRegistry => "verifiedregistry",
+        }
+    }
+}
+
+impl TryFrom<String> for CallerType {
+    type Error = crate::export::error::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        // Names are the ones used by the Filecoin builtin actors
+        match value.as_str() {
+            "system" => Ok(CallerType::System),
+            "init" => Ok(CallerType::Init),
+            "cron" => Ok(CallerType::Cron),
+            "account" => Ok(CallerType::Account),
+            "storagepower" => Ok(CallerType::Power),
+            "storageminer" => Ok(CallerType::Miner),
+            "storagemarket" => Ok(CallerType::Market),
+            "paymentchannel" => Ok(CallerType::PaymentChannel),
+            "multisig" => Ok(CallerType::Multisig),
+            "reward" => Ok(CallerType::Reward),
+            "verifiedregistry" => Ok(CallerType::VerifiedRegistry),
+            _ => Err(UnknownCallerType(value)),
+        }
+    }
+}
+
+/// Method numbers reserved by the Filecoin conventions, along with their usage
+pub const RESERVED_METHOD_NUMBERS: [(u64, &str); 2] = [
+    (0, "bare value transfers (METHOD_SEND)"),
+    (
+        CONSTRUCTOR_METHOD_NUMBER,
+        "the constructor (METHOD_CONSTRUCTOR)",
+    ),
+];
+
+/// Method an entry point is bound to, as specified in `#[fvm_export]`
+#[derive(Clone, Debug, Default)]
+pub struct Binding {
+    pub method: Method,
+    /// Literal the method was specified with, if any, to point diagnostics at it
+    pub literal: Option<TokenStream>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Method {
+    /// Method number specified as is
     Numeric(u64),
+    /// Method name to hash as specified in FRC-0042. An empty name is resolved from the method
+    /// identifier when converting the entry point
+    Name(String),
 }
 
 impl TryInto<u64> for Method {
@@ -59,6 +242,7 @@ impl TryInto<u64> for Method {
     fn try_into(self) -> std::result::Result<u64, Self::Error> {
         match self {
             Method::Numeric(num) => Ok(num),
+            Method::Name(name) => method_hash(&name),
         }
     }
 }
@@ -10,4 +10,28 @@ pub enum Error {
     /// This error is thrown when the numeric entry point value can not be parsed as u64
     #[error("invalid codec format, {0}")]
     InvalidNumericValue(String),
+    /// This error is thrown when the value provided for the method_name is not a literal string
+    #[error("invalid 'method_name' value")]
+    InvalidMethodNameValue,
+    /// This error is thrown when the method name is empty
+    #[error("method name can not be empty")]
+    EmptyMethodName,
+    /// This error is thrown when the method name does not start with an upper case letter or an underscore
+    #[error("method name '{0}' should start with an upper case letter or an underscore")]
+    IllegalMethodName(String),
+    /// This error is thrown when the method name contains characters that are not allowed
+    #[error("method name '{0}' should only contain alphanumeric characters and underscores")]
+    IllegalMethodNameSymbol(String),
+    /// This error is thrown when no valid method number can be derived from the method name hash
+    #[error("no valid method number could be derived from method name '{0}'")]
+    IndeterminableMethodNumber(String),
+    /// This error is thrown when the value of a caller guard is not the name of a state field
+    #[error("invalid '{0}' value, expected the name of a state field as a string")]
+    InvalidStateField(String),
+    /// This error is thrown when no actor type is specified for 'caller_type'
+    #[error("'caller_type' expects at least one actor type")]
+    EmptyCallerTypes,
+    /// This error is thrown when the actor type specified for 'caller_type' is not a builtin actor type
+    #[error("unknown actor type '{0}'")]
+    UnknownCallerType(String),
 }
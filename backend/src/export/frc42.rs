@@ -0,0 +1,131 @@
+//! Contains the logic to derive method numbers from method names, as specified in FRC-0042.
+
+use crate::export::error::Error;
+use crate::export::error::Error::{
+    EmptyMethodName, IllegalMethodName, IllegalMethodNameSymbol, IndeterminableMethodNumber,
+};
+
+/// Name of the constructor method as specified in FRC-0042
+pub const CONSTRUCTOR_METHOD_NAME: &str = "Constructor";
+/// Method number of the constructor method
+pub const CONSTRUCTOR_METHOD_NUMBER: u64 = 1;
+/// First method number that can be produced by hashing a method name
+pub const FIRST_METHOD_NUMBER: u64 = 1 << 24;
+
+// Length of the digest chunks used to find a valid method number
+const DIGEST_CHUNK_LENGTH: usize = 4;
+
+/// Compute the method number associated to a method name as specified in FRC-0042
+pub fn method_hash(method_name: &str) -> Result<u64, Error> {
+    check_method_name(method_name)?;
+
+    if method_name == CONSTRUCTOR_METHOD_NAME {
+        return Ok(CONSTRUCTOR_METHOD_NUMBER);
+    }
+
+    let digest = blake2b_simd::Params::new()
+        .hash_length(64)
+        .to_state()
+        .update(format!("1|{}", method_name).as_bytes())
+        .finalize();
+
+    // Use the first chunk of the digest that gives a method number in the allowed range
+    for chunk in digest.as_bytes().chunks(DIGEST_CHUNK_LENGTH) {
+        if chunk.len() < DIGEST_CHUNK_LENGTH {
+            break;
+        }
+        let method_number = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as u64;
+        if method_number >= FIRST_METHOD_NUMBER {
+            return Ok(method_number);
+        }
+    }
+
+    Err(IndeterminableMethodNumber(method_name.to_string()))
+}
+
+/// Derive a FRC-0042 method name from a Rust method identifier (e.g. `balance_of` => `BalanceOf`)
+pub fn method_name_from_ident(ident: &str) -> String {
+    ident
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+// Check that a method name follows FRC-0042 conventions
+fn check_method_name(method_name: &str) -> Result<(), Error> {
+    let first_letter = match method_name.chars().next() {
+        Some(first_letter) => first_letter,
+        None => return Err(EmptyMethodName),
+    };
+
+    // Method name should start with an upper case letter or an underscore
+    if !(first_letter.is_ascii_uppercase() || first_letter == '_') {
+        return Err(IllegalMethodName(method_name.to_string()));
+    }
+
+    // Method name should only contain alphanumeric characters and underscores
+    if method_name
+        .chars()
+        .any(|c| !(c.is_ascii_alphanumeric() || c == '_'))
+    {
+        return Err(IllegalMethodNameSymbol(method_name.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructor_method_number() {
+        assert_eq!(
+            method_hash("Constructor").unwrap(),
+            CONSTRUCTOR_METHOD_NUMBER
+        );
+    }
+
+    #[test]
+    fn known_method_numbers() {
+        // Reference value for the FRC-0046 receiver hook
+        assert_eq!(method_hash("Receive").unwrap(), 3726118371);
+    }
+
+    #[test]
+    fn hashed_method_number_in_range() {
+        let method_number = method_hash("BalanceOf").unwrap();
+        assert!(method_number >= FIRST_METHOD_NUMBER);
+        assert!(method_number <= u32::MAX as u64);
+    }
+
+    #[test]
+    fn invalid_method_names() {
+        assert_eq!(
+            method_hash("").unwrap_err().to_string(),
+            "method name can not be empty"
+        );
+        assert_eq!(
+            method_hash("transfer").unwrap_err().to_string(),
+            "method name 'transfer' should start with an upper case letter or an underscore"
+        );
+        assert_eq!(
+            method_hash("Trans-fer").unwrap_err().to_string(),
+            "method name 'Trans-fer' should only contain alphanumeric characters and underscores"
+        );
+    }
+
+    #[test]
+    fn name_from_ident() {
+        assert_eq!(method_name_from_ident("transfer"), "Transfer");
+        assert_eq!(method_name_from_ident("balance_of"), "BalanceOf");
+        assert_eq!(method_name_from_ident("total_supply_2"), "TotalSupply2");
+    }
+}
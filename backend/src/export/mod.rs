@@ -1,2 +1,3 @@
 pub mod attrs;
 mod error;
+pub mod frc42;
@@ -0,0 +1,70 @@
+//! Codegen has the logic of code generation for our actor interfaces through the `#[fvm_interface]`
+//! macro.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+
+use crate::abi;
+use crate::actor::codegen::client;
+use crate::ast;
+
+impl ToTokens for ast::ActorInterface {
+    fn to_tokens(&self, into: &mut TokenStream) {
+        let trait_name = &self.rust_name;
+        let definition = &self.definition;
+        let macro_name = Ident::new(
+            &format!(
+                "__fvm_interface_{}_{}",
+                self.name,
+                definition_hash(definition)
+            ),
+            Span::call_site(),
+        );
+        let client_name = Ident::new(&format!("{}Client", self.name), Span::call_site());
+        let client = client(
+            &client_name,
+            &self.entry_points,
+            &trait_name.to_token_stream(),
+        );
+        let interface = abi::actor_interface(&self.name, &self.entry_points);
+
+        quote!(
+            // Implementations with `#[fvm_actor]` call this macro, named after the trait, to bind
+            // their entry points as declared by the trait. It is exported at the root of the crate
+            // for implementations in other crates, under a name unique to the trait definition.
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #macro_name {
+                ($($implementation:tt)*) => {
+                    fvm_rs_sdk::actor::fvm_actor_interface! {
+                        #definition
+                        $($implementation)*
+                    }
+                };
+            }
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            pub use #macro_name as #trait_name;
+
+            #client
+
+            impl #client_name {
+                /// JSON descriptor of the interface, listing its entry points along with their
+                /// method number, mutability, arguments and return type.
+                pub const INTERFACE: &'static str = #interface;
+            }
+        )
+        .to_tokens(into);
+    }
+}
+
+// Short hash of the trait definition, to tell apart the macros of traits sharing a name
+fn definition_hash(definition: &TokenStream) -> String {
+    blake2b_simd::Params::new()
+        .hash_length(8)
+        .to_state()
+        .update(definition.to_string().as_bytes())
+        .finalize()
+        .to_hex()
+        .to_string()
+}
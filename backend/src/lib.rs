@@ -6,9 +6,13 @@ pub use crate::error::Diagnostic;
 
 #[macro_use]
 mod error;
+pub mod abi;
 pub mod actor;
 pub mod ast;
+pub mod event;
 pub mod export;
+pub mod interface;
 pub mod payload;
+pub mod schema;
 pub mod state;
 mod utils;
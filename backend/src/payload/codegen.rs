@@ -3,15 +3,28 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use crate::abi;
 use crate::ast;
+use crate::schema;
 
 impl ToTokens for ast::PayloadStruct {
     fn to_tokens(&self, into: &mut TokenStream) {
-        // Add derive for serialize & deserialize
+        let name = self.rust_name.clone();
+        let layout = abi::payload_layout(self);
+        let schema = schema::payload_schema(self);
+
+        // Add derive for serialize & deserialize, and expose the layout of the payload
         *into = (quote! {
             #[derive(fvm_rs_sdk::encoding::tuple::Serialize_tuple, fvm_rs_sdk::encoding::tuple::Deserialize_tuple)]
             #[serde( crate = "fvm_rs_sdk::encoding::serde")]
             #into
+
+            impl #name {
+                /// JSON descriptor of the payload layout, listing its fields in serialization order.
+                pub const PAYLOAD_LAYOUT: &'static str = #layout;
+                /// IPLD Schema of the payload.
+                pub const IPLD_SCHEMA: &'static str = #schema;
+            }
         })
             .to_token_stream();
     }
@@ -34,6 +47,13 @@ mod tests {
             pub struct MockStruct {
                 pub count: u64
             }
+
+            impl MockStruct {
+                /// JSON descriptor of the payload layout, listing its fields in serialization order.
+                pub const PAYLOAD_LAYOUT: &'static str = "{\"codec\":\"dag-cbor\",\"encoding\":\"tuple\",\"fields\":[{\"name\":\"count\",\"type\":\"u64\"}],\"name\":\"MockStruct\"}";
+                /// IPLD Schema of the payload.
+                pub const IPLD_SCHEMA: &'static str = "type MockStruct struct {\n  count Int\n} representation tuple";
+            }
         })
             .to_tokens(&mut expected_final_stream);
 
@@ -57,13 +77,22 @@ mod tests {
                 let ast_struct = ast::PayloadStruct {
                     rust_name: s.ident.to_token_stream(),
                     name: s.ident.to_string(),
+                    fields: vec![ast::PayloadStructField {
+                        rust_name: quote!(count),
+                        name: String::from("count"),
+                        ty: syn::parse_quote!(u64),
+                        bytes: false,
+                    }],
                     codec: DagCbor,
                 };
 
                 // Create ast::Program
                 let program = ast::Program {
                     payload_structs: vec![ast_struct],
+                    event_structs: vec![],
+                    migration_hooks: vec![],
                     actor_implementation: None,
+                    actor_interface: None,
                     state_structs: vec![],
                 };
 
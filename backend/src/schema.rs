@@ -0,0 +1,443 @@
+//! Schema renders the IPLD Schema (DMT) of state and payload structures, so that their blocks can be
+//! decoded without the Rust types.
+
+use quote::ToTokens;
+
+use crate::ast;
+use crate::payload::attrs::Codec as PayloadCodec;
+use crate::state::attrs::Codec as StateCodec;
+use crate::utils::type_to_string;
+
+/// Render the IPLD Schema of a state structure, based on the codec used to store it
+pub fn state_schema(state: &ast::StateStruct) -> String {
+    // Lazy fields hold the link to the block of their value, and collections the link to their root
+    let types: Vec<syn::Type> = state
+        .serialized_fields
+        .iter()
+        .map(|field| {
+            let ty: syn::Type = match (field.collection, option_inner(&field.ty)) {
+                (true, Some(_)) => syn::parse_quote!(Option<Cid>),
+                (true, None) => syn::parse_quote!(Cid),
+                (false, _) => field.ty.clone(),
+            };
+            match field.lazy {
+                true => syn::parse_quote!(Lazy<#ty>),
+                false => ty,
+            }
+        })
+        .collect();
+    let fields: Vec<(&str, &syn::Type, bool)> = state
+        .serialized_fields
+        .iter()
+        .zip(types.iter())
+        .map(|(field, ty)| (field.name.as_str(), ty, field.bytes && !field.lazy))
+        .collect();
+
+    let schema = match state.codec {
+        StateCodec::DagCbor => struct_schema(&state.name, &fields, Some("tuple")),
+        // Map is the default representation of structs
+        StateCodec::DagCborMap => struct_schema(&state.name, &fields, None),
+        // Raw state objects are opaque bytes
+        StateCodec::Raw => format!("type {} bytes", state.name),
+    };
+
+    // Versioned states are stored along with a tag and the version of their layout
+    match state.version {
+        Some(_) => format!(
+            "{}\n{}",
+            schema,
+            struct_schema(
+                &format!("Versioned{}", state.name),
+                &[
+                    ("tag", &syn::parse_quote!(String), false),
+                    ("version", &syn::parse_quote!(u64), false),
+                    ("state", &syn::parse_str(&state.name).unwrap(), false),
+                ],
+                Some("tuple"),
+            )
+        ),
+        None => schema,
+    }
+}
+
+/// Render the IPLD Schema of a payload structure
+pub fn payload_schema(payload: &ast::PayloadStruct) -> String {
+    let fields: Vec<(&str, &syn::Type, bool)> = payload
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), &field.ty, field.bytes))
+        .collect();
+
+    match payload.codec {
+        PayloadCodec::DagCbor => struct_schema(&payload.name, &fields, Some("tuple")),
+    }
+}
+
+/// Render the IPLD Schema type of a value, such as an argument of an entry point. Types that are not
+/// known are referenced by their name, expected to be the one of a payload.
+pub fn value_schema(ty: &syn::Type) -> String {
+    value_type(ty)
+}
+
+/// Names of the types referenced by an IPLD Schema type, that are not kinds of the data model
+pub fn referenced_types(schema: &str) -> Vec<String> {
+    schema
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|name| {
+            !matches!(
+                *name,
+                "" | "nullable" | "Bool" | "Int" | "Float" | "String" | "Bytes" | "Null" | "Any"
+            )
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Key type of a map nested in the given type that IPLD can not represent, as the keys of an IPLD map
+/// have to be strings. Types that are not known are expected to be represented as strings.
+pub fn non_string_map_key(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Reference(reference) => non_string_map_key(&reference.elem),
+        syn::Type::Paren(paren) => non_string_map_key(&paren.elem),
+        syn::Type::Group(group) => non_string_map_key(&group.elem),
+        syn::Type::Array(array) => non_string_map_key(&array.elem),
+        syn::Type::Slice(slice) => non_string_map_key(&slice.elem),
+        syn::Type::Tuple(tuple) => tuple.elems.iter().find_map(non_string_map_key),
+        syn::Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            match (
+                segment.ident.to_string().as_str(),
+                type_arguments(segment).as_slice(),
+            ) {
+                ("HashMap" | "BTreeMap", [key, value]) => {
+                    // Known kinds, lists, maps and links are not strings
+                    let key_type = ipld_type(key);
+                    let invalid = matches!(
+                        key_type.as_str(),
+                        "Bool" | "Int" | "Float" | "Bytes" | "Null" | "Any"
+                    ) || !key_type.chars().all(char::is_alphanumeric);
+                    match invalid {
+                        true => Some(type_to_string(&key.to_token_stream())),
+                        false => non_string_map_key(value),
+                    }
+                }
+                (_, arguments) => arguments
+                    .iter()
+                    .find_map(|argument| non_string_map_key(argument)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether a field is serialized as bytes by its serde attributes, such as
+/// `#[serde(with = "serde_bytes")]`
+pub fn serialized_as_bytes(attrs: &[syn::Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            syn::Meta::List(list) => list.nested.iter().any(|nested| match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+                    if name_value.path.is_ident("with") =>
+                {
+                    match &name_value.lit {
+                        syn::Lit::Str(module) => matches!(
+                            module.value().rsplit("::").next(),
+                            Some("serde_bytes" | "strict_bytes" | "bigint_ser")
+                        ),
+                        _ => false,
+                    }
+                }
+                _ => false,
+            }),
+            _ => false,
+        })
+}
+
+// Fields are given along with whether their serde attributes serialize them as bytes
+fn struct_schema(
+    name: &str,
+    fields: &[(&str, &syn::Type, bool)],
+    representation: Option<&str>,
+) -> String {
+    let mut schema = format!("type {} struct {{\n", name);
+    for (field_name, ty, bytes) in fields {
+        let field_type = match (*bytes, option_inner(ty)) {
+            (true, Some(_)) => String::from("nullable Bytes"),
+            (true, None) => String::from("Bytes"),
+            (false, _) => value_type(ty),
+        };
+        schema.push_str(&format!("  {} {}\n", field_name, field_type));
+    }
+    schema.push('}');
+    if let Some(representation) = representation {
+        schema.push_str(&format!(" representation {}", representation));
+    }
+
+    schema
+}
+
+// Render the type of a struct field or of a collection element, optional values are nullable
+fn value_type(ty: &syn::Type) -> String {
+    match option_inner(ty) {
+        Some(inner) => format!("nullable {}", ipld_type(inner)),
+        None => ipld_type(ty),
+    }
+}
+
+// Get the type of an optional value, if the given type is an `Option`
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let ("Option", [inner]) = (
+                segment.ident.to_string().as_str(),
+                type_arguments(segment).as_slice(),
+            ) {
+                return Some(inner);
+            }
+        }
+    }
+    None
+}
+
+// Map a Rust type to its IPLD Schema representation. Types that are not known are expected to be
+// described by their own schema.
+fn ipld_type(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Reference(reference) => ipld_type(&reference.elem),
+        syn::Type::Paren(paren) => ipld_type(&paren.elem),
+        syn::Type::Group(group) => ipld_type(&group.elem),
+        syn::Type::Array(array) => list_type(&array.elem),
+        syn::Type::Slice(slice) => list_type(&slice.elem),
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => String::from("Null"),
+        syn::Type::Path(path) => {
+            let segment = match path.path.segments.last() {
+                Some(segment) => segment,
+                None => return String::from("Any"),
+            };
+            let arguments = type_arguments(segment);
+
+            match (segment.ident.to_string().as_str(), arguments.as_slice()) {
+                ("bool", _) => String::from("Bool"),
+                (
+                    "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                    | "i128" | "isize" | "ActorID" | "ChainEpoch" | "MethodNum",
+                    _,
+                ) => String::from("Int"),
+                ("f32" | "f64", _) => String::from("Float"),
+                ("String" | "str", _) => String::from("String"),
+                // Only wrappers serializing their value as bytes are known to be bytes
+                (
+                    "Address" | "BigIntDe" | "BigIntSer" | "RawBytes" | "BytesKey" | "BytesDe"
+                    | "BytesSer" | "Byte32De",
+                    _,
+                ) => String::from("Bytes"),
+                // Big integers are serialized as their sign and digits by default
+                ("TokenAmount" | "BigInt" | "BigUint", _) => String::from("Any"),
+                ("Cid", _) => String::from("&Any"),
+                // Links to anonymous types are not expressible, they link to any block
+                ("Lazy", [inner]) => match ipld_type(inner) {
+                    name if name.chars().all(char::is_alphanumeric) => format!("&{}", name),
+                    _ => String::from("&Any"),
+                },
+                ("Option", [inner]) => ipld_type(inner),
+                ("Box" | "Rc" | "Arc", [inner]) => ipld_type(inner),
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => list_type(inner),
+                ("HashMap" | "BTreeMap", [key, value]) => {
+                    format!("{{{}:{}}}", ipld_type(key), value_type(value))
+                }
+                (name, _) => String::from(name),
+            }
+        }
+        _ => String::from("Any"),
+    }
+}
+
+// Byte sequences are lists of integers too, unless serialized with a serde attribute
+fn list_type(inner: &syn::Type) -> String {
+    format!("[{}]", value_type(inner))
+}
+
+fn type_arguments(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::attrs::{HashFunction, Version};
+    use quote::quote;
+
+    fn state_field(name: &str, ty: syn::Type) -> ast::StateStructField {
+        ast::StateStructField {
+            rust_name: quote!(name),
+            name: String::from(name),
+            struct_name: syn::parse_quote!(MockStruct),
+            ty,
+            lazy: false,
+            collection: false,
+            bytes: false,
+        }
+    }
+
+    #[test]
+    fn state_struct_schema() {
+        let fields = vec![
+            state_field("count", syn::parse_quote!(u64)),
+            state_field("owner", syn::parse_quote!(Option<Address>)),
+            state_field("data", syn::parse_quote!(Vec<u8>)),
+            state_field(
+                "names",
+                syn::parse_quote!(HashMap<String, Vec<Option<i32>>>),
+            ),
+            ast::StateStructField {
+                collection: true,
+                ..state_field("balances", syn::parse_quote!(collections::Map<u64, u64>))
+            },
+            ast::StateStructField {
+                collection: true,
+                ..state_field("orders", syn::parse_quote!(Vec<u64>))
+            },
+            ast::StateStructField {
+                collection: true,
+                ..state_field("archive", syn::parse_quote!(Option<Map<u64, u64>>))
+            },
+            state_field("registry", syn::parse_quote!(Map)),
+            state_field("config", syn::parse_quote!(Config)),
+            ast::StateStructField {
+                lazy: true,
+                ..state_field("metadata", syn::parse_quote!(Metadata))
+            },
+        ];
+        let mut state = ast::StateStruct {
+            rust_name: quote!(MockStruct),
+            name: String::from("MockStruct"),
+            fields: fields.clone(),
+            serialized_fields: fields,
+            codec: StateCodec::DagCbor,
+            hash: HashFunction::Blake2b256,
+            version: None,
+        };
+
+        assert_eq!(
+            state_schema(&state),
+            "type MockStruct struct {\n  count Int\n  owner nullable Bytes\n  data [Int]\n  names {String:[nullable Int]}\n  balances &Any\n  orders &Any\n  archive nullable &Any\n  registry Map\n  config Config\n  metadata &Metadata\n} representation tuple"
+        );
+
+        state.codec = StateCodec::Raw;
+        assert_eq!(state_schema(&state), "type MockStruct bytes");
+
+        state.codec = StateCodec::DagCborMap;
+        state.serialized_fields.truncate(1);
+        state.version = Some(Version {
+            number: 2,
+            span: proc_macro2::Span::call_site(),
+        });
+        assert_eq!(
+            state_schema(&state),
+            "type MockStruct struct {\n  count Int\n}\ntype VersionedMockStruct struct {\n  tag String\n  version Int\n  state MockStruct\n} representation tuple"
+        );
+    }
+
+    #[test]
+    fn non_string_map_keys() {
+        let valid: Vec<syn::Type> = vec![
+            syn::parse_quote!(HashMap<String, u64>),
+            syn::parse_quote!(BTreeMap<Name, HashMap<String, u64>>),
+            syn::parse_quote!(Vec<(u64, u64)>),
+            syn::parse_quote!(collections::Map<u64, u64>),
+        ];
+        for ty in valid.iter() {
+            assert_eq!(non_string_map_key(ty), None);
+        }
+
+        let invalid: Vec<(syn::Type, &str)> = vec![
+            (syn::parse_quote!(HashMap<u64, String>), "u64"),
+            (syn::parse_quote!(BTreeMap<Address, u64>), "Address"),
+            (syn::parse_quote!(HashMap<Cid, u64>), "Cid"),
+            (syn::parse_quote!(HashMap<(u64, u64), u64>), "(u64, u64)"),
+            (
+                syn::parse_quote!(Option<Vec<HashMap<String, BTreeMap<bool, u64>>>>),
+                "bool",
+            ),
+        ];
+        for (ty, key) in invalid.iter() {
+            assert_eq!(non_string_map_key(ty).as_deref(), Some(*key));
+        }
+    }
+
+    #[test]
+    fn byte_fields_schema() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote!(#[serde(with = "serde_bytes")]),
+            syn::parse_quote!(#[serde(with = "fvm_shared::bigint::bigint_ser")]),
+            syn::parse_quote!(#[serde(rename = "serde_bytes")]),
+            syn::parse_quote!(#[lazy]),
+        ];
+        assert!(serialized_as_bytes(&attrs[0..1]));
+        assert!(serialized_as_bytes(&attrs[1..2]));
+        assert!(!serialized_as_bytes(&attrs[2..]));
+
+        let fields = vec![
+            state_field("data", syn::parse_quote!(Vec<u8>)),
+            state_field("hash", syn::parse_quote!([u8; 32])),
+            state_field("supply", syn::parse_quote!(TokenAmount)),
+            state_field("balance", syn::parse_quote!(BigIntDe)),
+            state_field("params", syn::parse_quote!(RawBytes)),
+            ast::StateStructField {
+                bytes: true,
+                ..state_field("raw", syn::parse_quote!(Vec<u8>))
+            },
+            ast::StateStructField {
+                bytes: true,
+                ..state_field("limit", syn::parse_quote!(Option<TokenAmount>))
+            },
+        ];
+        let state = ast::StateStruct {
+            rust_name: quote!(MockStruct),
+            name: String::from("MockStruct"),
+            fields: fields.clone(),
+            serialized_fields: fields,
+            codec: StateCodec::DagCbor,
+            hash: HashFunction::Blake2b256,
+            version: None,
+        };
+
+        assert_eq!(
+            state_schema(&state),
+            "type MockStruct struct {\n  data [Int]\n  hash [Int]\n  supply Any\n  balance Bytes\n  params Bytes\n  raw Bytes\n  limit nullable Bytes\n} representation tuple"
+        );
+    }
+
+    #[test]
+    fn payload_struct_schema() {
+        let payload = ast::PayloadStruct {
+            rust_name: quote!(MockPayload),
+            name: String::from("MockPayload"),
+            fields: vec![ast::PayloadStructField {
+                rust_name: quote!(to),
+                name: String::from("to"),
+                ty: syn::parse_quote!(fvm_shared::address::Address),
+                bytes: false,
+            }],
+            codec: PayloadCodec::DagCbor,
+        };
+
+        assert_eq!(
+            payload_schema(&payload),
+            "type MockPayload struct {\n  to Bytes\n} representation tuple"
+        );
+    }
+}
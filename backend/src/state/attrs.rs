@@ -4,13 +4,28 @@ use std::convert::TryFrom;
 
 use crate::utils::AnyIdent;
 use anyhow::Result;
+use proc_macro2::Span;
 use syn::parse::{Parse, ParseStream, Result as SynResult};
 
-use crate::state::error::Error::{InvalidCodecFormat, UnknownAttribute, UnknownCodec};
+use crate::state::error::Error::{
+    InvalidCodecFormat, InvalidHashFormat, InvalidVersionFormat, UnknownAttribute, UnknownCodec,
+    UnknownHash,
+};
 
 #[derive(Clone, Debug)]
 pub enum StateAttr {
     Codec(Codec),
+    Hash(HashFunction),
+    Version(Version),
+    Lazy(bool),
+}
+
+/// Version of the layout of a state, along with the span of the literal declaring it to report
+/// missing migrations on it
+#[derive(Clone, Copy, Debug)]
+pub struct Version {
+    pub number: u64,
+    pub span: Span,
 }
 
 impl TryFrom<String> for StateAttr {
@@ -19,6 +34,12 @@ impl TryFrom<String> for StateAttr {
     fn try_from(attr: String) -> Result<Self, Self::Error> {
         match attr.as_str() {
             "codec" => Ok(StateAttr::Codec(Codec::default())),
+            "hash" => Ok(StateAttr::Hash(HashFunction::default())),
+            "version" => Ok(StateAttr::Version(Version {
+                number: 0,
+                span: Span::call_site(),
+            })),
+            "lazy" => Ok(StateAttr::Lazy(true)),
             _ => Err(UnknownAttribute(attr)),
         }
     }
@@ -46,14 +67,87 @@ impl Parse for StateAttr {
                 };
                 Ok(StateAttr::Codec(val))
             }
+            Ok(StateAttr::Hash(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                let val = match input.parse::<syn::LitStr>() {
+                    Ok(str) => match HashFunction::try_from(str.value()) {
+                        Ok(hash_function) => hash_function,
+                        Err(err) => return Err(original.error(format!("{}", err))),
+                    },
+                    Err(err) => {
+                        return Err(
+                            original.error(format!("{}", InvalidHashFormat(err.to_string())))
+                        )
+                    }
+                };
+                Ok(StateAttr::Hash(val))
+            }
+            Ok(StateAttr::Version(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                let span = input.span();
+                Ok(StateAttr::Version(Version {
+                    number: parse_version(input, &original)?,
+                    span,
+                }))
+            }
+            Ok(StateAttr::Lazy(value)) => Ok(StateAttr::Lazy(value)),
             Err(err) => Err(original.error(format!("{}", err))),
         }
     }
 }
 
+/// Attributes available for the `#[fvm_migrate]` procedural macro
+#[derive(Clone, Debug)]
+pub enum MigrateAttr {
+    From(u64),
+}
+
+impl TryFrom<String> for MigrateAttr {
+    type Error = crate::state::error::Error;
+
+    fn try_from(attr: String) -> Result<Self, Self::Error> {
+        match attr.as_str() {
+            "from" => Ok(MigrateAttr::From(0)),
+            _ => Err(UnknownAttribute(attr)),
+        }
+    }
+}
+
+impl Parse for MigrateAttr {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let original = input.fork();
+        let attr: AnyIdent = input.parse()?;
+        let attr = attr.0;
+
+        match MigrateAttr::try_from(attr.to_string()) {
+            Ok(MigrateAttr::From(_)) => {
+                input.parse::<syn::token::Eq>()?;
+                Ok(MigrateAttr::From(parse_version(input, &original)?))
+            }
+            Err(err) => Err(original.error(format!("{}", err))),
+        }
+    }
+}
+
+// Parse the version of a state layout, expected as an integer literal
+fn parse_version(input: ParseStream, original: ParseStream) -> SynResult<u64> {
+    match input.parse::<syn::LitInt>() {
+        Ok(int) => match int.base10_parse::<u64>() {
+            Ok(version) => Ok(version),
+            Err(err) => Err(original.error(format!("{}", InvalidVersionFormat(err.to_string())))),
+        },
+        Err(err) => Err(original.error(format!("{}", InvalidVersionFormat(err.to_string())))),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Codec {
+    /// DAG-CBOR with fields encoded as a tuple
     DagCbor,
+    /// DAG-CBOR with fields encoded as a map keyed by field names
+    DagCborMap,
+    /// Opaque bytes produced by the `RawState` implementation of the state object
+    Raw,
 }
 
 impl Default for Codec {
@@ -68,7 +162,31 @@ impl TryFrom<String> for Codec {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         match value.as_str() {
             "dag-cbor" => Ok(Codec::DagCbor),
+            "dag-cbor-map" => Ok(Codec::DagCborMap),
+            "raw" => Ok(Codec::Raw),
             _ => Err(UnknownCodec(value)),
         }
     }
 }
+
+/// Hash function used to compute the CID of the state blocks
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum HashFunction {
+    #[default]
+    Blake2b256,
+    Sha2_256,
+    Keccak256,
+}
+
+impl TryFrom<String> for HashFunction {
+    type Error = crate::state::error::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "blake2b-256" => Ok(HashFunction::Blake2b256),
+            "sha2-256" => Ok(HashFunction::Sha2_256),
+            "keccak-256" => Ok(HashFunction::Keccak256),
+            _ => Err(UnknownHash(value)),
+        }
+    }
+}
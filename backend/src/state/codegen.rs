@@ -1,86 +1,242 @@
 //! Codegen has the logic of code generation for our actor through the `#[fvm_state]` macro.
 
-use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 
 use crate::ast;
-use crate::state::attrs::Codec;
+use crate::schema;
+use crate::state::attrs::{Codec, HashFunction};
 
 impl ToTokens for ast::StateStruct {
     fn to_tokens(&self, into: &mut TokenStream) {
-        // Add derive for serialize & deserialize
-        *into = (quote! {
-            #[derive(fvm_rs_sdk::encoding::tuple::Serialize_tuple, fvm_rs_sdk::encoding::tuple::Deserialize_tuple)]
-            #[serde( crate = "fvm_rs_sdk::encoding::serde")]
-            #into
-        })
-            .to_token_stream();
-
+        // Add derive for serialize & deserialize, raw state objects handle their own conversion
         match self.codec {
             Codec::DagCbor => {
+                *into = (quote! {
+                    #[derive(fvm_rs_sdk::encoding::tuple::Serialize_tuple, fvm_rs_sdk::encoding::tuple::Deserialize_tuple)]
+                    #[serde( crate = "fvm_rs_sdk::encoding::serde")]
+                    #into
+                })
+                .to_token_stream();
+            }
+            Codec::DagCborMap => {
+                *into = (quote! {
+                    #[derive(fvm_rs_sdk::encoding::serde::Serialize, fvm_rs_sdk::encoding::serde::Deserialize)]
+                    #[serde( crate = "fvm_rs_sdk::encoding::serde")]
+                    #into
+                })
+                .to_token_stream();
+            }
+            Codec::Raw => {}
+        }
+
+        // Multihash code used to compute the CID of the state block
+        let hash_code = match self.hash {
+            HashFunction::Blake2b256 => quote!(fvm_rs_sdk::cid::Code::Blake2b256),
+            HashFunction::Sha2_256 => quote!(fvm_rs_sdk::cid::Code::Sha2_256),
+            HashFunction::Keccak256 => quote!(fvm_rs_sdk::cid::Code::Keccak256),
+        };
+
+        match (&self.codec, self.version) {
+            (Codec::DagCbor | Codec::DagCborMap, None) => {
                 let name = &self.rust_name;
                 quote!(
                     impl fvm_rs_sdk::state::StateObject for #name {
-                        fn load() -> Self {
-                            use fvm_rs_sdk::encoding::CborStore;
-                            // First, load the current state root.
-                            let root = match fvm_rs_sdk::syscall::sself::root() {
-                                Ok(root) => root,
-                                Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                                    Some(format!("failed to get root: {:?}", err).as_str()),
-                                ),
-                            };
+                        const HASH_CODE: fvm_rs_sdk::cid::Code = #hash_code;
 
-                            // Load the actor state from the state tree.
-                            match fvm_rs_sdk::state::cbor::CborBlockstore.get_cbor::<Self>(&root) {
-                                Ok(Some(state)) => state,
-                                Ok(None) => fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                                    Some("state does not exist"),
-                                ),
-                                Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                                    Some(format!("failed to get state: {}", err).as_str()),
-                                ),
-                            }
+                        fn try_decode(bytes: &[u8]) -> std::result::Result<Self, fvm_rs_sdk::state::error::Error> {
+                            fvm_rs_sdk::encoding::from_slice::<Self>(bytes)
+                                .map_err(|err| fvm_rs_sdk::state::error::Error::GetStateFailed(err.into()))
                         }
 
-                        fn save(&self) -> fvm_rs_sdk::cid::Cid {
-                            let serialized = match fvm_rs_sdk::encoding::to_vec(self) {
-                                Ok(s) => s,
-                                Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
-                                    Some(format!("failed to serialize state: {:?}", err).as_str()),
-                                ),
-                            };
-                            let cid = match fvm_rs_sdk::syscall::ipld::put(
-                                fvm_rs_sdk::cid::Code::Blake2b256.into(),
-                                fvm_rs_sdk::state::cbor::SIZE,
+                        fn try_encode(&self) -> std::result::Result<(fvm_rs_sdk::cid::Cid, Vec<u8>), fvm_rs_sdk::state::error::Error> {
+                            // Lazy fields and collections are written with the hash function of the state
+                            let serialized = fvm_rs_sdk::state::cbor::with_hash_code(Self::HASH_CODE, || fvm_rs_sdk::encoding::to_vec(self))
+                                .map_err(fvm_rs_sdk::state::error::Error::SerializationFailed)?;
+                            let cid = fvm_rs_sdk::state::cbor::block_cid(
+                                Self::HASH_CODE,
                                 fvm_rs_sdk::encoding::DAG_CBOR,
-                                serialized.as_slice(),
-                            ) {
-                                Ok(cid) => cid,
-                                Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
-                                    Some(format!("failed to store initial state: {:}", err).as_str()),
-                                ),
+                                &serialized,
+                            );
+                            Ok((cid, serialized))
+                        }
+                    }
+                ).to_tokens(into);
+            }
+            (Codec::DagCbor | Codec::DagCborMap, Some(version)) => {
+                let name = &self.rust_name;
+                let span = version.span;
+                let version = version.number;
+                let current = Literal::u64_unsuffixed(version);
+
+                // Every prior layout is upgraded through the chain of migrations leading to the
+                // current one. A hook with mismatched layouts fails to compile, and so does a
+                // missing one, reported on the version of the state.
+                let hook = |from| format_ident!("__fvm_migrate_from_{}", from, span = span);
+                let migrations = (0..version).map(|from| {
+                    let stored = Literal::u64_unsuffixed(from);
+                    let first = hook(from);
+                    let next = (from + 1..version).map(hook);
+                    quote_spanned! {span=>
+                        #stored => fvm_rs_sdk::state::version::migrate(bytes, Self::#first)
+                            #(.map(Self::#next))*,
+                    }
+                });
+
+                // Hooks that are not declared resolve to these fallbacks, as inherent functions
+                // take precedence over trait ones. Their bound is never satisfied, so that the
+                // state fails to compile with an error naming the missing `#[fvm_migrate]`.
+                let fallbacks = format_ident!("__FvmMigrations{}", self.name);
+                let fallback_hooks = (0..version).map(|from| {
+                    let hook = migration_ident(from);
+                    let from = Literal::u64_unsuffixed(from);
+                    quote! {
+                        fn #hook<P, N>(_previous: P) -> N
+                        where
+                            Self: fvm_rs_sdk::state::version::Migration<#from>,
+                        {
+                            unreachable!()
+                        }
+                    }
+                });
+
+                quote!(
+                    #[doc(hidden)]
+                    #[allow(dead_code, non_camel_case_types)]
+                    trait #fallbacks {
+                        #(#fallback_hooks)*
+                    }
+
+                    impl #fallbacks for #name {}
+
+                    impl fvm_rs_sdk::state::StateObject for #name {
+                        const HASH_CODE: fvm_rs_sdk::cid::Code = #hash_code;
+
+                        fn try_decode(bytes: &[u8]) -> std::result::Result<Self, fvm_rs_sdk::state::error::Error> {
+                            // Decode the state with the layout it was stored with, upgrading it if needed.
+                            let state = match fvm_rs_sdk::state::version::stored_version(bytes) {
+                                #current => fvm_rs_sdk::state::version::decode::<Self>(bytes),
+                                #(#migrations)*
+                                stored => {
+                                    return Err(fvm_rs_sdk::state::error::Error::UnsupportedVersion(stored, #current))
+                                }
                             };
-                            if let Err(err) = fvm_rs_sdk::syscall::sself::set_root(&cid) {
-                                fvm_rs_sdk::syscall::vm::abort(
-                                    fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                                    Some(format!("failed to set root cid: {:}", err).as_str()),
-                                );
-                            }
-                            cid
+                            state.map_err(|err| fvm_rs_sdk::state::error::Error::GetStateFailed(err.into()))
+                        }
+
+                        fn try_encode(&self) -> std::result::Result<(fvm_rs_sdk::cid::Cid, Vec<u8>), fvm_rs_sdk::state::error::Error> {
+                            // Lazy fields and collections are written with the hash function of the state
+                            let serialized = fvm_rs_sdk::state::cbor::with_hash_code(Self::HASH_CODE, || fvm_rs_sdk::state::version::encode(#current, self))
+                                .map_err(fvm_rs_sdk::state::error::Error::SerializationFailed)?;
+                            let cid = fvm_rs_sdk::state::cbor::block_cid(
+                                Self::HASH_CODE,
+                                fvm_rs_sdk::encoding::DAG_CBOR,
+                                &serialized,
+                            );
+                            Ok((cid, serialized))
+                        }
+                    }
+                ).to_tokens(into);
+            }
+            (Codec::Raw, _) => {
+                let name = &self.rust_name;
+                quote!(
+                    impl fvm_rs_sdk::state::StateObject for #name {
+                        const HASH_CODE: fvm_rs_sdk::cid::Code = #hash_code;
+
+                        fn try_decode(bytes: &[u8]) -> std::result::Result<Self, fvm_rs_sdk::state::error::Error> {
+                            <Self as fvm_rs_sdk::state::RawState>::from_bytes(bytes)
+                                .map_err(fvm_rs_sdk::state::error::Error::FromBytesFailed)
+                        }
+
+                        fn try_encode(&self) -> std::result::Result<(fvm_rs_sdk::cid::Cid, Vec<u8>), fvm_rs_sdk::state::error::Error> {
+                            let bytes = <Self as fvm_rs_sdk::state::RawState>::to_bytes(self)
+                                .map_err(fvm_rs_sdk::state::error::Error::ToBytesFailed)?;
+                            let cid = fvm_rs_sdk::state::cbor::block_cid(
+                                Self::HASH_CODE,
+                                fvm_rs_sdk::shared::IPLD_RAW,
+                                &bytes,
+                            );
+                            Ok((cid, bytes))
                         }
                     }
                 ).to_tokens(into);
             }
         }
+
+        // Accessors to the lazy fields, loading their value on first access
+        let accessors = self
+            .serialized_fields
+            .iter()
+            .filter(|field| field.lazy)
+            .map(|field| {
+                let field_name = &field.rust_name;
+                let ty = &field.ty;
+                let mut_accessor = format_ident!("{}_mut", field.name);
+                let visibility = match self.fields.iter().any(|f| f.name == field.name) {
+                    true => quote!(pub),
+                    false => quote!(),
+                };
+                let get_doc = format!(
+                    "Returns the value of `{}`, reading its block on first access",
+                    field.name
+                );
+                let get_mut_doc = format!(
+                    "Returns the value of `{}` to modify it, its block being written on save",
+                    field.name
+                );
+                quote!(
+                    #[doc = #get_doc]
+                    #visibility fn #field_name(&self) -> &#ty {
+                        self.#field_name.get()
+                    }
+
+                    #[doc = #get_mut_doc]
+                    #visibility fn #mut_accessor(&mut self) -> &mut #ty {
+                        self.#field_name.get_mut()
+                    }
+                )
+            });
+
+        // Expose the IPLD Schema of the state, to decode it without the Rust types
+        let name = &self.rust_name;
+        let schema = schema::state_schema(self);
+        quote!(
+            impl #name {
+                /// IPLD Schema of the state block.
+                pub const IPLD_SCHEMA: &'static str = #schema;
+
+                #(#accessors)*
+            }
+        )
+        .to_tokens(into);
     }
 }
 
+impl ToTokens for ast::MigrationHook {
+    fn to_tokens(&self, into: &mut TokenStream) {
+        // The hook is declared in an implementation of the state, where the state codegen finds it
+        // under a name derived from the version it migrates from
+        let name = &self.rust_name;
+        let hidden_name = migration_ident(self.from);
+        let previous = &self.previous;
+        let next = &self.next;
+
+        quote!(
+            #[doc(hidden)]
+            pub fn #hidden_name(previous: #previous) -> #next {
+                Self::#name(previous)
+            }
+        )
+        .to_tokens(into);
+    }
+}
+
+// Name of the function generated for the migration hook of a given version
+fn migration_ident(from: u64) -> Ident {
+    format_ident!("__fvm_migrate_from_{}", from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,60 +256,30 @@ mod tests {
             }
 
             impl fvm_rs_sdk::state::StateObject for MockStruct {
-                fn load() -> Self {
-                    use fvm_rs_sdk::encoding::CborStore;
-                    // First, load the current state root.
-                    let root = match fvm_rs_sdk::syscall::sself::root() {
-                        Ok(root) => root,
-                        Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                            Some(format!("failed to get root: {:?}", err).as_str()),
-                        ),
-                    };
+                const HASH_CODE: fvm_rs_sdk::cid::Code = fvm_rs_sdk::cid::Code::Blake2b256;
 
-                    // Load the actor state from the state tree.
-                    match fvm_rs_sdk::state::cbor::CborBlockstore.get_cbor::<Self>(&root) {
-                        Ok(Some(state)) => state,
-                        Ok(None) => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                            Some("state does not exist"),
-                        ),
-                        Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                            Some(format!("failed to get state: {}", err).as_str()),
-                        ),
-                    }
+                fn try_decode(bytes: &[u8]) -> std::result::Result<Self, fvm_rs_sdk::state::error::Error> {
+                    fvm_rs_sdk::encoding::from_slice::<Self>(bytes)
+                        .map_err(|err| fvm_rs_sdk::state::error::Error::GetStateFailed(err.into()))
                 }
 
-                fn save(&self) -> fvm_rs_sdk::cid::Cid {
-                    let serialized = match fvm_rs_sdk::encoding::to_vec(self) {
-                        Ok(s) => s,
-                        Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
-                            Some(format!("failed to serialize state: {:?}", err).as_str()),
-                        ),
-                    };
-                    let cid = match fvm_rs_sdk::syscall::ipld::put(
-                        fvm_rs_sdk::cid::Code::Blake2b256.into(),
-                        fvm_rs_sdk::state::cbor::SIZE,
+                fn try_encode(&self) -> std::result::Result<(fvm_rs_sdk::cid::Cid, Vec<u8>), fvm_rs_sdk::state::error::Error> {
+                    // Lazy fields and collections are written with the hash function of the state
+                    let serialized = fvm_rs_sdk::state::cbor::with_hash_code(Self::HASH_CODE, || fvm_rs_sdk::encoding::to_vec(self))
+                        .map_err(fvm_rs_sdk::state::error::Error::SerializationFailed)?;
+                    let cid = fvm_rs_sdk::state::cbor::block_cid(
+                        Self::HASH_CODE,
                         fvm_rs_sdk::encoding::DAG_CBOR,
-                        serialized.as_slice(),
-                    ) {
-                        Ok(cid) => cid,
-                        Err(err) => fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_SERIALIZATION.value(),
-                            Some(format!("failed to store initial state: {:}", err).as_str()),
-                        ),
-                    };
-                    if let Err(err) = fvm_rs_sdk::syscall::sself::set_root(&cid) {
-                        fvm_rs_sdk::syscall::vm::abort(
-                            fvm_rs_sdk::shared::error::ExitCode::USR_ILLEGAL_STATE.value(),
-                            Some(format!("failed to set root cid: {:}", err).as_str()),
-                        );
-                    }
-                    cid
+                        &serialized,
+                    );
+                    Ok((cid, serialized))
                 }
             }
+
+            impl MockStruct {
+                /// IPLD Schema of the state block.
+                pub const IPLD_SCHEMA: &'static str = "type MockStruct struct {\n  count Int\n} representation tuple";
+            }
         })
             .to_tokens(&mut expected_final_stream);
 
@@ -187,21 +313,30 @@ mod tests {
                         name,
                         struct_name: s.ident.clone(),
                         ty: field.ty.clone(),
+                        lazy: false,
+                        collection: false,
+                        bytes: false,
                     });
                 }
 
                 let ast_struct = ast::StateStruct {
                     rust_name: s.ident.to_token_stream(),
                     name: s.ident.to_string(),
-                    fields,
+                    fields: fields.clone(),
+                    serialized_fields: fields,
                     codec: DagCbor,
+                    hash: HashFunction::Blake2b256,
+                    version: None,
                 };
 
                 // Create ast::Program
                 let program = ast::Program {
                     state_structs: vec![ast_struct],
                     actor_implementation: None,
+                    actor_interface: None,
                     payload_structs: vec![],
+                    event_structs: vec![],
+                    migration_hooks: vec![],
                 };
 
                 program.try_to_tokens(&mut token_stream).unwrap();
@@ -211,4 +346,23 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn migration_hook() {
+        let hook = ast::MigrationHook {
+            rust_name: format_ident!("from_v1"),
+            from: 1,
+            previous: syn::parse_quote!(MockStructV1),
+            next: syn::parse_quote!(MockStruct),
+        };
+
+        let expected = quote! {
+            #[doc(hidden)]
+            pub fn __fvm_migrate_from_1(previous: MockStructV1) -> MockStruct {
+                Self::from_v1(previous)
+            }
+        };
+
+        assert_eq!(hook.to_token_stream().to_string(), expected.to_string());
+    }
 }
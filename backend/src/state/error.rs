@@ -10,4 +10,13 @@ pub enum Error {
     /// This error is thrown when the codec is not a literal string
     #[error("invalid codec format, {0}")]
     InvalidCodecFormat(String),
+    /// This error is thrown when the specified hash function is not handled
+    #[error("unknown hash function '{0}'")]
+    UnknownHash(String),
+    /// This error is thrown when the hash function is not a literal string
+    #[error("invalid hash format, {0}")]
+    InvalidHashFormat(String),
+    /// This error is thrown when the version of a state layout is not an integer literal
+    #[error("invalid version format, {0}")]
+    InvalidVersionFormat(String),
 }
@@ -27,3 +27,52 @@ pub trait TryToTokens {
         Ok(tokens)
     }
 }
+
+// Render a type as it would be written in code (e.g. `Vec < u8 >` => `Vec<u8>`)
+pub(crate) fn type_to_string(arg_type: &TokenStream) -> String {
+    let raw = arg_type.to_string();
+    let chars: Vec<char> = raw.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+
+    let mut rendered = String::with_capacity(raw.len());
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_whitespace() {
+            // Only keep spaces separating two words (e.g. `dyn Trait`) or following a comma or a semicolon
+            let previous = i.checked_sub(1).and_then(|i| chars.get(i));
+            let next = chars.get(i + 1);
+            let keep = match (previous, next) {
+                (Some(previous), Some(next)) => {
+                    (is_word(previous) && is_word(next)) || *previous == ',' || *previous == ';'
+                }
+                _ => false,
+            };
+            if !keep {
+                continue;
+            }
+        }
+        rendered.push(*c);
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn render_types() {
+        assert_eq!(type_to_string(&quote!(u64)), "u64");
+        assert_eq!(type_to_string(&quote!(Vec<u8>)), "Vec<u8>");
+        assert_eq!(type_to_string(&quote!([u8; 5])), "[u8; 5]");
+        assert_eq!(
+            type_to_string(&quote!(fvm_rs_sdk::cid::Cid)),
+            "fvm_rs_sdk::cid::Cid"
+        );
+        assert_eq!(
+            type_to_string(&quote!((String, HashMap<u64, String>))),
+            "(String, HashMap<u64, String>)"
+        );
+    }
+}
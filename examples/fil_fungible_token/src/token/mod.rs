@@ -1,9 +1,10 @@
 pub mod state;
 pub mod types;
 
+use fvm_rs_sdk::actor::ActorError;
 use fvm_rs_sdk::shared::address::Address;
-use fvm_rs_sdk::shared::ActorID;
 
+use crate::token::state::StateError;
 use fvm_rs_sdk::shared::error::ExitCode;
 use thiserror::Error;
 
@@ -15,15 +16,21 @@ pub enum TokenError {
         "expected {0:?} to be a resolvable id address but none found when attempting to resolve"
     )]
     InvalidIdAddress(Address),
-    #[error("caller is not actor owner: found {0}, expected: {0}")]
-    CallerNotOwner(ActorID, ActorID),
+    #[error(transparent)]
+    State(#[from] StateError),
 }
 
-impl TokenError {
-    pub fn abort(&self) -> ! {
-        fvm_rs_sdk::syscall::vm::abort(
-            ExitCode::USR_UNSPECIFIED.value(),
-            Some(&format!("{}", &self)),
-        )
+impl From<TokenError> for ActorError {
+    fn from(err: TokenError) -> Self {
+        let exit_code = match &err {
+            TokenError::InvalidNegative(_) | TokenError::InvalidIdAddress(_) => {
+                ExitCode::USR_ILLEGAL_ARGUMENT
+            }
+            TokenError::State(StateError::InsufficentAllowance { .. }) => {
+                ExitCode::USR_INSUFFICIENT_FUNDS
+            }
+            TokenError::State(_) => ExitCode::USR_ILLEGAL_STATE,
+        };
+        ActorError::new(exit_code, err.to_string())
     }
 }
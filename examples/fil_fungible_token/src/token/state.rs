@@ -2,9 +2,9 @@ use fvm_rs_sdk::shared::bigint::bigint_ser::BigIntDe;
 use fvm_rs_sdk::shared::bigint::Zero;
 use fvm_rs_sdk::shared::econ::TokenAmount;
 use fvm_rs_sdk::shared::ActorID;
+use fvm_rs_sdk::state::collections::Map;
 use fvm_rs_sdk::state::*;
 
-use crate::ExitCode;
 use num_traits::Signed;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -31,21 +31,14 @@ pub enum StateError {
         supply: TokenAmount,
         delta: TokenAmount,
     },
-}
-
-impl StateError {
-    pub fn abort(&self) -> ! {
-        fvm_rs_sdk::syscall::vm::abort(
-            ExitCode::USR_UNSPECIFIED.value(),
-            Some(&format!("{}", &self)),
-        )
-    }
+    #[error(transparent)]
+    Collection(#[from] fvm_rs_sdk::state::collections::Error),
 }
 
 type Result<T> = std::result::Result<T, StateError>;
 
 /// Token state structure
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 #[fvm_state]
 pub struct TokenState {
     /// Name of the token
@@ -56,17 +49,19 @@ pub struct TokenState {
     pub owner: ActorID,
     /// Total supply of token
     pub supply: BigIntDe,
-    /// HashMap<ActorID, TokenAmount> of balances as a Hamt
-    pub balances: HashMap<ActorID, BigIntDe>,
-    /// HashMap<ActorId, HashMap<ActorID, TokenAmount>> as a Hamt. Allowances are stored balances[owner][operator]
-    pub allowances: HashMap<ActorID, HashMap<ActorID, BigIntDe>>,
+    /// Map<ActorID, TokenAmount> of balances as a Hamt
+    #[collection]
+    pub balances: Map<ActorID, BigIntDe>,
+    /// Map<ActorId, HashMap<ActorID, TokenAmount>> as a Hamt. Allowances are stored balances[owner][operator]
+    #[collection]
+    pub allowances: Map<ActorID, HashMap<ActorID, BigIntDe>>,
 }
 
 impl TokenState {
     /// Get the balance of an ActorID from the currently stored state
     pub fn get_balance(&self, owner: ActorID) -> Result<TokenAmount> {
-        Ok(match self.balances.get(&owner) {
-            Some(amount) => amount.clone().0,
+        Ok(match self.balances.get(&owner)? {
+            Some(amount) => amount.0,
             None => TokenAmount::zero(),
         })
     }
@@ -85,10 +80,10 @@ impl TokenState {
             return self.get_balance(owner);
         }
 
-        let balance = self.balances.get(&owner);
+        let balance = self.balances.get(&owner)?;
 
         let new_balance = match balance {
-            Some(existing_amount) => existing_amount.clone().0 + delta,
+            Some(existing_amount) => existing_amount.0 + delta,
             None => (*delta).clone(),
         };
 
@@ -101,7 +96,7 @@ impl TokenState {
             });
         }
 
-        self.balances.insert(owner, BigIntDe(new_balance.clone()));
+        self.balances.set(owner, BigIntDe(new_balance.clone()))?;
 
         Ok(new_balance)
     }
@@ -126,7 +121,7 @@ impl TokenState {
     ///
     /// If an existing allowance cannot be found, it is implicitly assumed to be zero
     pub fn get_allowance_between(&self, owner: ActorID, operator: ActorID) -> Result<TokenAmount> {
-        let owner_allowances = self.allowances.get(&owner);
+        let owner_allowances = self.allowances.get(&owner)?;
         match owner_allowances {
             Some(allowances) => match allowances.get(&operator) {
                 Some(token_amount) => Ok(token_amount.clone().0),
@@ -149,8 +144,8 @@ impl TokenState {
         }
 
         // get or create the owner's allowance map
-        let mut allowance_map = match self.allowances.get(&owner) {
-            Some(allowances) => allowances.clone(),
+        let mut allowance_map = match self.allowances.get(&owner)? {
+            Some(allowances) => allowances,
             None => {
                 // the owner doesn't have any allowances, and the delta is negative, this is a no-op
                 if delta.is_negative() {
@@ -178,10 +173,10 @@ impl TokenState {
 
         // if the owner-allowance map is empty, remove it from the global allowances map
         if allowance_map.is_empty() {
-            self.allowances.remove(&owner);
+            self.allowances.delete(&owner)?;
         } else {
             // else update the global-allowance map
-            self.allowances.insert(owner, allowance_map);
+            self.allowances.set(owner, allowance_map)?;
         }
 
         Ok(new_allowance)
@@ -191,11 +186,13 @@ impl TokenState {
     ///
     /// If that map becomes empty, it is removed from the root map.
     pub fn attempt_revoke_allowance(&mut self, owner: ActorID, operator: ActorID) -> Result<()> {
-        let allowance_map = self.allowances.get_mut(&owner);
-        if let Some(map) = allowance_map {
+        let allowance_map = self.allowances.get(&owner)?;
+        if let Some(mut map) = allowance_map {
             map.remove(&operator);
             if map.is_empty() {
-                self.allowances.remove(&owner);
+                self.allowances.delete(&owner)?;
+            } else {
+                self.allowances.set(owner, map)?;
             }
         }
 
@@ -228,11 +225,11 @@ impl TokenState {
 
         let new_allowance = current_allowance - amount;
 
-        let owner_allowances = self.allowances.get_mut(&owner);
+        let owner_allowances = self.allowances.get(&owner)?;
         // to reach here, allowance must have been previously non zero; so safe to assume the map exists
-        let owner_allowances = owner_allowances.unwrap();
-        // TODO Might not work
+        let mut owner_allowances = owner_allowances.unwrap();
         owner_allowances.insert(operator, BigIntDe(new_allowance.clone()));
+        self.allowances.set(owner, owner_allowances)?;
 
         Ok(new_allowance)
     }
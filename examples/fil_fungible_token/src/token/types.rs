@@ -1,37 +1,57 @@
+use fvm_rs_sdk::actor::{fvm_export, fvm_interface, Context};
 use fvm_rs_sdk::payload::*;
 use fvm_rs_sdk::shared::address::Address;
 use fvm_rs_sdk::shared::bigint::bigint_ser::BigIntDe;
 
+use crate::token::TokenError;
+
+#[fvm_interface]
 pub trait FrcXXXToken {
     /// Returns the name of the token
+    #[fvm_export(method_num = 2)]
     fn name(&self) -> String;
 
     /// Returns the ticker symbol of the token
+    #[fvm_export(method_num = 3)]
     fn symbol(&self) -> String;
 
     /// Returns the total amount of the token in existence
+    #[fvm_export(method_num = 4)]
     fn total_supply(&self) -> BigIntDe;
 
     /// Gets the balance of a particular address (if it exists)
     ///
     /// This will method attempt to resolve addresses to ID-addresses
-    fn balance_of(&self, params: Address) -> BigIntDe;
+    #[fvm_export(method_num = 5)]
+    fn balance_of(&self, params: Address) -> Result<BigIntDe, TokenError>;
 
     /// Atomically increase the amount that a operator can pull from the owner account
     ///
     /// The increase must be non-negative. Returns the new allowance between those two addresses if
     /// successful
-    fn increase_allowance(&mut self, params: ChangeAllowanceParams) -> AllowanceReturn;
+    #[fvm_export(method_num = 6)]
+    fn increase_allowance(
+        &mut self,
+        params: ChangeAllowanceParams,
+    ) -> Result<AllowanceReturn, TokenError>;
 
     /// Atomically decrease the amount that a operator can pull from an account
     ///
     /// The decrease must be non-negative. The resulting allowance is set to zero if the decrease is
     /// more than the current allowance. Returns the new allowance between the two addresses if
     /// successful
-    fn decrease_allowance(&mut self, params: ChangeAllowanceParams) -> AllowanceReturn;
+    #[fvm_export(method_num = 7)]
+    fn decrease_allowance(
+        &mut self,
+        params: ChangeAllowanceParams,
+    ) -> Result<AllowanceReturn, TokenError>;
 
     /// Set the allowance a operator has on the owner's account to zero
-    fn revoke_allowance(&mut self, params: RevokeAllowanceParams) -> AllowanceReturn;
+    #[fvm_export(method_num = 8)]
+    fn revoke_allowance(
+        &mut self,
+        params: RevokeAllowanceParams,
+    ) -> Result<AllowanceReturn, TokenError>;
 
     /// Get the allowance between two addresses
     ///
@@ -39,13 +59,15 @@ pub trait FrcXXXToken {
     /// address of the owner cannot be resolved, this method returns an error. If the owner can be
     /// resolved, but the operator address is not registered with an allowance, an implicit allowance
     /// of 0 is returned
-    fn allowance(&self, params: GetAllowanceParams) -> AllowanceReturn;
+    #[fvm_export(method_num = 9)]
+    fn allowance(&self, params: GetAllowanceParams) -> Result<AllowanceReturn, TokenError>;
 
     /// Mint tokens on a given account, increasing the total supply
     ///
     /// When minting token:
     /// - Caller should be actor owner
-    fn mint(&mut self, params: MintParams) -> MintReturn;
+    #[fvm_export(method_num = 10, caller = "owner")]
+    fn mint(&mut self, params: MintParams) -> Result<MintReturn, TokenError>;
 
     /// Burn tokens from the caller's account, decreasing the total supply
     ///
@@ -53,10 +75,16 @@ pub trait FrcXXXToken {
     /// - Any owner MUST be allowed to burn their own tokens
     /// - The balance of the owner MUST decrease by the amount burned
     /// - This method MUST revert if the burn amount is more than the owner's balance
-    fn burn(&mut self, params: BurnParams) -> BurnReturn;
+    #[fvm_export(method_num = 11)]
+    fn burn(&mut self, ctx: Context, params: BurnParams) -> Result<BurnReturn, TokenError>;
 
     /// Transfer tokens from one account to another
-    fn transfer(&mut self, params: TransferParams) -> TransferReturn;
+    #[fvm_export(method_num = 12)]
+    fn transfer(
+        &mut self,
+        ctx: Context,
+        params: TransferParams,
+    ) -> Result<TransferReturn, TokenError>;
 }
 
 pub type SupplyReturn = BigIntDe;
@@ -1,26 +1,511 @@
-use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export, ActorError, Context};
+use fvm_rs_sdk::event::fvm_event;
+use fvm_rs_sdk::shared::bigint::bigint_ser::BigIntDe;
+use fvm_rs_sdk::shared::econ::TokenAmount;
+use fvm_rs_sdk::shared::ActorID;
 use fvm_rs_sdk::state::*;
 
 #[derive(Clone, Debug, Default)]
-#[fvm_state]
+#[fvm_state(version = 2)]
 pub struct State {
     pub value: u64,
+    pub additions: u64,
+    pub owner: Option<ActorID>,
+}
+
+/// Layout of the state before additions were counted
+#[fvm_state]
+pub struct StateV0 {
+    pub value: u64,
+}
+
+/// Layout of the state before it had an owner
+#[fvm_state]
+pub struct StateV1 {
+    pub value: u64,
+    pub additions: u64,
 }
 
-#[fvm_actor]
 impl State {
-    #[fvm_export(method_num = 1)]
-    pub fn new() -> Self {
-        State { value: 0 }
+    #[fvm_migrate(from = 0)]
+    pub fn from_v0(previous: StateV0) -> StateV1 {
+        StateV1 {
+            value: previous.value,
+            additions: 0,
+        }
     }
 
-    #[fvm_export(method_num = 2)]
-    pub fn add(&mut self, value: u64) {
-        self.value += value
+    // States stored before owners were recorded have none
+    #[fvm_migrate(from = 1)]
+    pub fn from_v1(previous: StateV1) -> Self {
+        State {
+            value: previous.value,
+            additions: previous.additions,
+            owner: None,
+        }
+    }
+}
+
+#[fvm_event]
+pub struct Added {
+    #[indexed]
+    pub value: u64,
+}
+
+#[fvm_actor(client_feature = "client")]
+impl State {
+    #[fvm_export(constructor)]
+    pub fn new(owner: ActorID) -> Self {
+        State {
+            value: 0,
+            additions: 0,
+            owner: Some(owner),
+        }
+    }
+
+    #[fvm_export(method_num = 2, nonreentrant)]
+    pub fn add(&mut self, value: u64) -> Result<(), ActorError> {
+        self.value = self
+            .value
+            .checked_add(value)
+            .ok_or_else(|| ActorError::illegal_argument("value overflow"))?;
+        self.additions += 1;
+        Ok(())
     }
 
     #[fvm_export(method_num = 3)]
     pub fn read(&self) -> u64 {
         self.value
     }
+
+    #[fvm_export(method_num = 4, caller_type = [account, multisig])]
+    pub fn reset(&mut self) {
+        self.value = 0
+    }
+
+    #[fvm_export(method_num = 5, payable)]
+    pub fn deposit(&self, #[value] value: TokenAmount) -> BigIntDe {
+        BigIntDe(value)
+    }
+
+    #[fvm_export(method_num = 6)]
+    pub fn whoami(ctx: Context) -> u64 {
+        ctx.caller
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fvm_rs_sdk::actor::INIT_ACTOR_ID;
+    use fvm_rs_sdk::cid::{Cid, Code};
+    use fvm_rs_sdk::encoding::{RawBytes, DAG_CBOR};
+    use fvm_rs_sdk::event::{emit, Event};
+    use fvm_rs_sdk::shared::actor::builtin::Type;
+    use fvm_rs_sdk::shared::address::Address;
+    use fvm_rs_sdk::shared::error::ExitCode;
+    use fvm_rs_sdk::shared::receipt::Receipt;
+    use fvm_rs_sdk::testing::MockRuntime;
+
+    #[test]
+    fn add_and_read() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap();
+        let ret = rt.call(invoke, 3, &RawBytes::default()).unwrap().unwrap();
+
+        assert_eq!(ret.deserialize::<u64>().unwrap(), 5);
+    }
+
+    #[test]
+    fn event_recorded() {
+        let rt = MockRuntime::new();
+
+        emit(&Added { value: 5 }).unwrap();
+
+        let events = rt.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0], Added { value: 5 }.to_actor_event().unwrap());
+    }
+
+    #[test]
+    fn missing_state_not_found() {
+        let rt = MockRuntime::new();
+
+        // The actor has no state root before it is constructed
+        assert!(matches!(
+            State::try_load(),
+            Err(error::Error::StateNotFound)
+        ));
+        assert!(matches!(
+            lock::try_load::<State>(),
+            Err(error::Error::StateNotFound)
+        ));
+        rt.set_caller(100);
+        let err = rt.call(invoke, 3, &RawBytes::default()).unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_STATE);
+
+        // Nor a state once its root links to a block that is not stored
+        let root = cbor::block_cid(Code::Blake2b256, DAG_CBOR, b"missing");
+        fvm_rs_sdk::syscall::sself::set_root(&root).unwrap();
+        assert!(matches!(
+            State::try_load(),
+            Err(error::Error::StateNotFound)
+        ));
+    }
+
+    #[test]
+    fn previous_layout_migrated_on_load() {
+        let rt = MockRuntime::new();
+        StateV0 { value: 5 }.save();
+
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(2u64).unwrap())
+            .unwrap();
+
+        let state = State::load();
+        assert_eq!(state.value, 7);
+        assert_eq!(state.additions, 1);
+        assert_eq!(state.owner, None);
+
+        let bytes = fvm_rs_sdk::syscall::ipld::get(&rt.root().unwrap()).unwrap();
+        assert_eq!(version::stored_version(&bytes), 2);
+    }
+
+    #[test]
+    fn unchanged_state_not_dirty() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        let root = rt.root().unwrap();
+
+        // Resetting a state with no value leaves the state root as it was
+        rt.set_caller(100);
+        rt.set_actor_type(100, Type::Account);
+        rt.call(invoke, 4, &RawBytes::default()).unwrap();
+        assert_eq!(rt.root(), Some(root));
+
+        let mut state = State::load();
+        assert!(!state.is_dirty().unwrap());
+        state.value += 1;
+        assert!(state.is_dirty().unwrap());
+        assert_ne!(state.save(), root);
+        assert!(!state.is_dirty().unwrap());
+    }
+
+    #[test]
+    fn reentrant_call_denied() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+
+        // Adding releases the lock it holds once it saved the state
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap();
+        assert_eq!(lock::state_root().unwrap(), rt.root().unwrap());
+
+        // Lock the state as an entry point sending a message to another actor would
+        lock::lock(State::HASH_CODE).unwrap();
+        let err = rt
+            .call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+
+        // Reading the state looks through the lock
+        assert_eq!(dispatch::read().unwrap(), 5);
+        assert_ne!(lock::state_root().unwrap(), rt.root().unwrap());
+    }
+
+    #[test]
+    fn reentrant_save_keeps_lock() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap();
+
+        // Resetting while the state is locked saves the state under the lock
+        lock::lock(State::HASH_CODE).unwrap();
+        rt.set_actor_type(100, Type::Account);
+        rt.call(invoke, 4, &RawBytes::default()).unwrap();
+        assert_eq!(dispatch::read().unwrap(), 0);
+
+        let err = rt
+            .call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+
+        // Only the entry point holding the lock releases it
+        lock::unlock().unwrap();
+        assert_eq!(lock::state_root().unwrap(), rt.root().unwrap());
+        assert_eq!(State::load().value, 0);
+    }
+
+    #[test]
+    fn lock_released_on_abort() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap();
+
+        // Adding fails once the state is locked, reverting the lock
+        let err = rt
+            .call(invoke, 2, &RawBytes::serialize(u64::MAX).unwrap())
+            .unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_ARGUMENT);
+
+        rt.call(invoke, 2, &RawBytes::serialize(1u64).unwrap())
+            .unwrap();
+        assert_eq!(dispatch::read().unwrap(), 6);
+    }
+
+    #[test]
+    fn failed_call_releases_lock() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        dispatch::new(100).unwrap();
+        dispatch::add(5).unwrap();
+
+        // Dispatching does not revert the state as an abort would, the entry point releases the
+        // lock it holds itself
+        let err = dispatch::add(u64::MAX).unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_ARGUMENT);
+        assert_eq!(lock::state_root().unwrap(), rt.root().unwrap());
+
+        dispatch::add(1).unwrap();
+        assert_eq!(dispatch::read().unwrap(), 6);
+    }
+
+    #[test]
+    fn aborted_call_reverted() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(u64::MAX).unwrap())
+            .unwrap();
+        let root = rt.root();
+
+        // Overflowing the value aborts once the state is locked
+        let err = rt
+            .call(invoke, 2, &RawBytes::serialize(1u64).unwrap())
+            .unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_ARGUMENT);
+        assert_eq!(rt.root(), root);
+        assert_eq!(dispatch::read().unwrap(), u64::MAX);
+    }
+
+    #[fvm_state(lazy)]
+    struct Registry {
+        count: u64,
+        #[lazy]
+        metadata: String,
+    }
+
+    #[test]
+    fn lazy_field_written_when_modified() {
+        let rt = MockRuntime::new();
+        let root = Registry {
+            count: 1,
+            metadata: Lazy::new(String::from("metadata")),
+        }
+        .save();
+
+        // The root block only links to the block of the lazy field
+        let bytes = fvm_rs_sdk::syscall::ipld::get(&root).unwrap();
+        let (count, link): (u64, Cid) = fvm_rs_sdk::encoding::from_slice(&bytes).unwrap();
+        assert_eq!(count, 1);
+
+        let mut registry = Registry::load();
+        assert_eq!(registry.metadata(), "metadata");
+        assert_eq!(registry.save(), root);
+
+        registry.metadata_mut().push_str(" updated");
+        // Checking the state does not write the modified field
+        assert!(registry.is_dirty().unwrap());
+        assert!(registry.metadata.is_dirty());
+        assert_ne!(registry.save(), root);
+        assert_ne!(registry.metadata.flush().unwrap(), link);
+
+        assert_eq!(Registry::load().metadata(), "metadata updated");
+        assert_eq!(rt.root(), Some(registry.save()));
+    }
+
+    #[fvm_state(lazy, hash = "sha2-256")]
+    struct Catalog {
+        #[collection]
+        entries: collections::Map<collections::BytesKey, u64>,
+        #[lazy]
+        description: String,
+    }
+
+    #[test]
+    fn fields_hashed_as_state() {
+        let _rt = MockRuntime::new();
+        let mut entries = collections::Map::new();
+        entries
+            .set(collections::BytesKey(b"entry".to_vec()), 1)
+            .unwrap();
+        let root = Catalog {
+            entries,
+            description: Lazy::new(String::from("description")),
+        }
+        .save();
+
+        // Blocks of the collections and lazy fields are hashed as the state block
+        let bytes = fvm_rs_sdk::syscall::ipld::get(&root).unwrap();
+        let (entries, description): (Cid, Cid) = fvm_rs_sdk::encoding::from_slice(&bytes).unwrap();
+        for cid in [root, entries, description] {
+            assert_eq!(cid.hash().code(), u64::from(Code::Sha2_256));
+        }
+    }
+
+    #[test]
+    fn constructor_called_by_other_actor() {
+        let rt = MockRuntime::new();
+        rt.set_caller(100);
+
+        let err = rt
+            .call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap_err();
+
+        assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+        assert!(rt.root().is_none());
+    }
+
+    #[test]
+    fn add_without_params() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+
+        let err = rt.call(invoke, 2, &RawBytes::default()).unwrap_err();
+
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_ARGUMENT);
+    }
+
+    #[test]
+    fn reset_guarded_by_caller_type() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        rt.set_caller(100);
+        rt.call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap();
+
+        let err = rt.call(invoke, 4, &RawBytes::default()).unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+
+        rt.set_actor_type(100, Type::Account);
+        rt.call(invoke, 4, &RawBytes::default()).unwrap();
+        assert_eq!(dispatch::read().unwrap(), 0);
+    }
+
+    #[test]
+    fn value_only_sent_to_payable() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        rt.call(invoke, 1, &RawBytes::serialize(100u64).unwrap())
+            .unwrap();
+        rt.set_value_received(TokenAmount::from(10));
+
+        let err = rt
+            .call(invoke, 2, &RawBytes::serialize(5u64).unwrap())
+            .unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::USR_ILLEGAL_ARGUMENT);
+
+        assert_eq!(dispatch::deposit().unwrap().0, TokenAmount::from(10));
+    }
+
+    #[test]
+    fn context_filled_in() {
+        let rt = MockRuntime::new();
+        rt.set_caller(100);
+
+        assert_eq!(dispatch::whoami().unwrap(), 100);
+        assert_eq!(
+            State::whoami(Context {
+                caller: 7,
+                ..Default::default()
+            }),
+            7
+        );
+    }
+
+    #[test]
+    fn typed_wrappers() {
+        let rt = MockRuntime::new();
+        rt.set_caller(INIT_ACTOR_ID);
+        dispatch::new(100).unwrap();
+
+        dispatch::add(3).unwrap();
+        dispatch::add(4).unwrap();
+
+        assert_eq!(dispatch::read().unwrap(), 7);
+    }
+
+    #[test]
+    fn dispatch_unknown_method() {
+        let _rt = MockRuntime::new();
+
+        let err = dispatch(42, &RawBytes::default()).unwrap_err();
+
+        assert_eq!(err.exit_code(), ExitCode::USR_UNHANDLED_MESSAGE);
+    }
+
+    #[test]
+    fn client_sends_typed_calls() {
+        let rt = MockRuntime::new();
+        rt.push_send_receipt(Receipt {
+            exit_code: ExitCode::OK,
+            return_data: RawBytes::default(),
+            gas_used: 0,
+        });
+        rt.push_send_receipt(Receipt {
+            exit_code: ExitCode::OK,
+            return_data: RawBytes::serialize(9u64).unwrap(),
+            gas_used: 0,
+        });
+        let client = StateClient::new(Address::new_id(200));
+
+        client.add(9).unwrap();
+        assert_eq!(client.read().unwrap(), 9);
+
+        let sends = rt.sends();
+        assert_eq!(sends[0].to, Address::new_id(200));
+        assert_eq!(sends[0].method, 2);
+        assert_eq!(sends[0].params, RawBytes::serialize(9u64).unwrap());
+        assert_eq!(sends[1].method, 3);
+    }
+
+    #[test]
+    fn client_propagates_exit_code() {
+        let rt = MockRuntime::new();
+        rt.push_send_receipt(Receipt {
+            exit_code: ExitCode::USR_FORBIDDEN,
+            return_data: RawBytes::default(),
+            gas_used: 0,
+        });
+        let client = StateClient::new(Address::new_id(200));
+
+        let err = client.read().unwrap_err();
+
+        assert_eq!(err.exit_code(), ExitCode::USR_FORBIDDEN);
+    }
 }
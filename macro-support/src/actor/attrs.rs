@@ -0,0 +1,15 @@
+use crate::utils::{generate_attr_getters, generate_attrs};
+use backend::actor::attrs::{ActorAttr, Reentrancy};
+use syn::parse::{Parse, ParseStream, Result};
+
+// Parsed attributes from a `#[fvm_actor(..)]`.
+generate_attrs!(ActorAttrs, ActorAttr);
+
+// Generate getters to retrieve attributes values
+generate_attr_getters!(
+    ActorAttrs,
+    [
+        (reentrancy, ActorAttr::Reentrancy, Reentrancy),
+        (client_feature, ActorAttr::ClientFeature, String),
+    ]
+);
@@ -10,4 +10,10 @@ pub enum Error {
     /// This error is thrown when the implementation for the actor interface is not for an expected structure
     #[error("expected implementation for type with no leading colon, 1 path segment, and no angle bracketed or parenthesized path arguments with #[fvm_actor]")]
     UnexpectedImplementationType,
+    /// This error is thrown when two entry points are bound to the same method number
+    #[error("method number {0} is bound to both '{1}' and '{2}'")]
+    DuplicateMethodNumber(u64, String, String),
+    /// This error is thrown when the reentrancy is set on an implementation bound by its interface
+    #[error("reentrancy can not be set on the implementation of an interface. Mark its entry points with 'nonreentrant' instead.")]
+    ReentrancyOnInterfaceImplementation,
 }
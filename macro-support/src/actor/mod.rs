@@ -1,2 +1,3 @@
+pub(crate) mod attrs;
 mod error;
 pub(crate) mod parser;
@@ -1,16 +1,23 @@
 //! Parser reads a source `TokenStream` to prepare the backend to generate custom code
 
+use std::collections::HashMap;
+use std::convert::TryInto;
+
 use crate::utils::{ConvertToAst, MacroParse};
-use backend::ast::ActorEntryPoint;
+use backend::actor::attrs::Reentrancy;
+use backend::ast::{ActorEntryPoint, Mutability};
 use backend::{ast, Diagnostic};
 use proc_macro2::TokenStream;
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 use syn::{Attribute, ImplItem, Item, Type};
 
+use crate::actor::attrs::ActorAttrs;
 use crate::actor::error::Error::{
-    ExpectedImplementation, GenericsOnInterface, UnexpectedImplementationType,
+    DuplicateMethodNumber, ExpectedImplementation, GenericsOnInterface,
+    ReentrancyOnInterfaceImplementation, UnexpectedImplementationType,
 };
 use crate::export::attrs::ExportAttrs;
+use crate::export::convert::strip_argument_attrs;
 
 impl<'a> ConvertToAst<()> for &'a mut syn::ItemImpl {
     type Target = ast::ActorImplementation;
@@ -40,22 +47,11 @@ impl<'a> ConvertToAst<()> for &'a mut syn::ItemImpl {
 
         for item in &self.items {
             if let ImplItem::Method(mut m) = item.clone() {
-                // Get export token stream
-                let filtered_attributes: Vec<&Attribute> = m
-                    .attrs
-                    .iter()
-                    .filter(|a| match a.path.segments.last() {
-                        Some(s) => s.to_token_stream().to_string().contains("fvm_export"),
-                        None => false,
-                    })
-                    .collect::<Vec<&Attribute>>();
-                if filtered_attributes.is_empty() {
-                    continue;
-                }
-
                 // Parse export attributes
-                let fvm_export_attr: &Attribute = filtered_attributes[0];
-                let export_attrs: ExportAttrs = fvm_export_attr.parse_args()?;
+                let export_attrs = match export_attrs(&m.attrs)? {
+                    Some(export_attrs) => export_attrs,
+                    None => continue,
+                };
 
                 // Generate ast entry point
                 let entry_point: ActorEntryPoint = (&mut m).convert(export_attrs)?;
@@ -63,24 +59,140 @@ impl<'a> ConvertToAst<()> for &'a mut syn::ItemImpl {
             }
         }
 
+        check_duplicate_bindings(&entry_points)?;
+
         Ok(ast::ActorImplementation {
             rust_name,
             name,
             entry_points,
+            client_feature: None,
         })
     }
 }
 
-impl<'a> MacroParse<&'a mut TokenStream> for syn::Item {
+// Parse the attributes of the `#[fvm_export]` attribute of a method, if any
+pub(crate) fn export_attrs(attrs: &[Attribute]) -> Result<Option<ExportAttrs>, Diagnostic> {
+    // Get export token stream
+    let filtered_attributes: Vec<&Attribute> = attrs
+        .iter()
+        .filter(|a| match a.path.segments.last() {
+            Some(s) => s.to_token_stream().to_string().contains("fvm_export"),
+            None => false,
+        })
+        .collect::<Vec<&Attribute>>();
+
+    match filtered_attributes.first() {
+        Some(fvm_export_attr) => Ok(Some(fvm_export_attr.parse_args()?)),
+        None => Ok(None),
+    }
+}
+
+// Ensure that no two entry points are bound to the same method number
+pub(crate) fn check_duplicate_bindings(entry_points: &[ActorEntryPoint]) -> Result<(), Diagnostic> {
+    let mut bound: HashMap<u64, &ActorEntryPoint> = HashMap::new();
+    let mut diagnostics = vec![];
+
+    for entry_point in entry_points {
+        let method_number: u64 = entry_point
+            .binding
+            .clone()
+            .try_into()
+            .map_err(|err| Diagnostic::error(format!("{}", err)))?;
+
+        match bound.get(&method_number) {
+            Some(previous) => {
+                let error = DuplicateMethodNumber(
+                    method_number,
+                    previous.name.clone(),
+                    entry_point.name.clone(),
+                );
+                // Point to both methods to ease debugging
+                diagnostics.push(Diagnostic::spanned_error(
+                    &previous.rust_name,
+                    format!("{}", error),
+                ));
+                diagnostics.push(Diagnostic::spanned_error(
+                    &entry_point.rust_name,
+                    format!("{}", error),
+                ));
+            }
+            None => {
+                bound.insert(method_number, entry_point);
+            }
+        }
+    }
+
+    Diagnostic::from_vec(diagnostics)
+}
+
+// Remove the attributes marking injected arguments from the methods of the implementation
+pub(crate) fn strip_impl_argument_attrs(item_impl: &mut syn::ItemImpl) {
+    for item in item_impl.items.iter_mut() {
+        if let ImplItem::Method(m) = item {
+            strip_argument_attrs(&mut m.sig);
+        }
+    }
+}
+
+// Whether the implementation is for a trait and relies on its `#[fvm_interface]` for its exports
+fn is_interface_implementation(item_impl: &syn::ItemImpl) -> bool {
+    item_impl.trait_.is_some()
+        && item_impl.items.iter().all(|item| match item {
+            ImplItem::Method(m) => !m.attrs.iter().any(|a| match a.path.segments.last() {
+                Some(s) => s.ident == "fvm_export",
+                None => false,
+            }),
+            _ => true,
+        })
+}
+
+impl<'a> MacroParse<(Option<ActorAttrs>, &'a mut TokenStream)> for syn::Item {
     fn macro_parse(
         self,
         program: &mut ast::Program,
-        tokens: &'a mut TokenStream,
+        (attrs, tokens): (Option<ActorAttrs>, &'a mut TokenStream),
     ) -> Result<(), Diagnostic> {
+        let attrs = attrs.unwrap_or_default();
+        let reentrancy = attrs.reentrancy().cloned().unwrap_or_default();
+        let client_feature = attrs.client_feature().cloned();
+
         // Match of Item types to parse & generate our AST
         match self {
+            // Trait implementations without exports get their bindings from the trait interface
+            Item::Impl(mut i) if is_interface_implementation(&i) => {
+                if reentrancy != Reentrancy::Allow {
+                    return Err(Diagnostic::error(format!(
+                        "{}",
+                        ReentrancyOnInterfaceImplementation
+                    )));
+                }
+                let trait_path = match &i.trait_ {
+                    Some((_, path, _)) => path.clone(),
+                    None => unreachable!(),
+                };
+                // The client feature is passed along to the macro of the interface with the
+                // implementation
+                if let Some(feature) = client_feature {
+                    i.attrs
+                        .push(syn::parse_quote!(#[fvm_actor(client_feature = #feature)]));
+                }
+                quote!(#trait_path! { #i }).to_tokens(tokens);
+            }
             Item::Impl(mut i) => {
-                program.actor_implementation = Some((&mut i).convert(())?);
+                let mut actor_implementation = (&mut i).convert(())?;
+                actor_implementation.client_feature = client_feature;
+                // Denying reentrancy locks the state in every entry point writing on it
+                if reentrancy == Reentrancy::Deny {
+                    for entry_point in actor_implementation.entry_points.iter_mut() {
+                        if matches!(entry_point.mutability, Mutability::Write)
+                            && !entry_point.constructor
+                        {
+                            entry_point.nonreentrant = true;
+                        }
+                    }
+                }
+                program.actor_implementation = Some(actor_implementation);
+                strip_impl_argument_attrs(&mut i);
                 i.to_tokens(tokens);
             }
             _ => {
@@ -121,7 +233,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        item.macro_parse(&mut program, &mut tokens).unwrap();
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
 
         let actor_implementation: &ActorImplementation = &program.actor_implementation.unwrap();
 
@@ -148,7 +261,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        if let Err(err) = item.macro_parse(&mut program, &mut tokens) {
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
             assert_eq!(
                 err.to_token_stream().to_string(),
                 "compile_error ! { \"implementation with #[fvm_actor] cannot have lifetime or type parameters.\" }"
@@ -157,4 +271,161 @@ mod tests {
             panic!("implementation with generics and #[fvm_actor] should cause an error")
         }
     }
+
+    #[test]
+    fn implementation_with_duplicate_bindings() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn add(&mut self, value: u64) {
+                    self.count += value
+                }
+
+                #[fvm_export(method_num = 2)]
+                pub fn sub(&mut self, value: u64) {
+                    self.count -= value
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"method number 2 is bound to both 'add' and 'sub'\" } compile_error ! { \"method number 2 is bound to both 'add' and 'sub'\" }"
+            )
+        } else {
+            panic!("implementation with duplicate method numbers should cause an error")
+        }
+    }
+
+    #[test]
+    fn implementation_denying_reentrancy() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(constructor)]
+                pub fn new() -> Self {
+                    Actor { count: 0 }
+                }
+
+                #[fvm_export(method_num = 2)]
+                pub fn add(&mut self, value: u64) {
+                    self.count += value
+                }
+
+                #[fvm_export(method_num = 3)]
+                pub fn read(&self) -> u64 {
+                    self.count
+                }
+            }
+        })
+        .unwrap();
+        let attrs: ActorAttrs = syn::parse2(quote! { reentrancy = "deny" }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        // Only entry points writing on an existing state are locked
+        let entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(!entry_points[0].nonreentrant);
+        assert!(entry_points[1].nonreentrant);
+        assert!(!entry_points[2].nonreentrant);
+    }
+
+    #[test]
+    fn interface_implementation_denying_reentrancy() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Counter for Actor {
+                fn add(&mut self, value: u64) {
+                    self.count += value
+                }
+            }
+        })
+        .unwrap();
+        let attrs: ActorAttrs = syn::parse2(quote! { reentrancy = "deny" }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(err) => assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"reentrancy can not be set on the implementation of an interface. Mark its entry points with 'nonreentrant' instead.\" }"
+            ),
+            Ok(_) => panic!("interface implementation denying reentrancy should throw an error"),
+        }
+    }
+
+    #[test]
+    fn implementation_with_client_feature() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn read(&self) -> u64 {
+                    self.count
+                }
+            }
+        })
+        .unwrap();
+        let attrs: ActorAttrs = syn::parse2(quote! { client_feature = "client" }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        assert_eq!(
+            program.actor_implementation.unwrap().client_feature,
+            Some(String::from("client"))
+        );
+    }
+
+    #[test]
+    fn interface_implementation_with_client_feature() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Counter for Actor {
+                fn add(&mut self, value: u64) {
+                    self.count += value
+                }
+            }
+        })
+        .unwrap();
+        let attrs: ActorAttrs = syn::parse2(quote! { client_feature = "client" }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        // The feature is passed along to the macro of the interface
+        assert!(tokens
+            .to_string()
+            .contains("# [fvm_actor (client_feature = \"client\")] impl Counter for Actor"));
+    }
+
+    #[test]
+    fn invalid_client_feature() {
+        match syn::parse2::<ActorAttrs>(quote! { client_feature = client }) {
+            Err(err) => assert_eq!(
+                err.to_string(),
+                "invalid client feature format, expected the name of a feature as a string literal"
+            ),
+            _ => panic!("client feature that is not a string literal should throw an error"),
+        }
+    }
 }
@@ -0,0 +1,9 @@
+use crate::utils::{generate_attr_getters, generate_attrs};
+use backend::event::attrs::EventAttr;
+use syn::parse::{Parse, ParseStream, Result};
+
+// Parsed attributes from a `#[fvm_event(..)]`.
+generate_attrs!(EventAttrs, EventAttr);
+
+// Generate getters to retrieve attributes values
+generate_attr_getters!(EventAttrs, [(name, EventAttr::Name, String),]);
@@ -0,0 +1,15 @@
+#[derive(thiserror::Error, Debug)]
+/// Errors related to actor's event structure parsing.
+pub enum Error {
+    /// This error is thrown when an event structure is declared with generics
+    #[error("structure with #[fvm_event] cannot have lifetime or type parameters.")]
+    GenericsOnStructure,
+    /// This error is thrown when procedural macro is not used on a structure
+    #[error("#[fvm_event] should be used with a structure.")]
+    ExpectedStructure,
+    /// This error is thrown when the fields of the structure are not named
+    #[error(
+        "structure with #[fvm_event] should have named fields, used as the keys of its entries."
+    )]
+    ExpectedNamedFields,
+}
@@ -0,0 +1,3 @@
+pub(crate) mod attrs;
+mod error;
+pub(crate) mod parser;
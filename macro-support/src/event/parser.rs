@@ -0,0 +1,133 @@
+//! Parser reads a source `TokenStream` to prepare the backend to generate custom code
+
+use crate::event::attrs::EventAttrs;
+use backend::{ast, Diagnostic};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+
+use crate::event::error::Error::{ExpectedNamedFields, ExpectedStructure, GenericsOnStructure};
+use crate::utils::{ConvertToAst, MacroParse};
+
+impl<'a> ConvertToAst<EventAttrs> for &'a mut syn::ItemStruct {
+    type Target = ast::EventStruct;
+
+    fn convert(self, attrs: EventAttrs) -> Result<Self::Target, Diagnostic> {
+        // No lifetime to make sure that we can handle it correctly
+        if !self.generics.params.is_empty() {
+            return Err(Diagnostic::error(format!("{}", GenericsOnStructure)));
+        }
+
+        let mut fields = Vec::new();
+        for field in self.fields.iter_mut() {
+            let ident = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => return Err(Diagnostic::error(format!("{}", ExpectedNamedFields))),
+            };
+
+            // Fields marked with `#[indexed]` are indexed, the attribute is only meaningful to us
+            let indexed = field.attrs.iter().any(|a| a.path.is_ident("indexed"));
+            field.attrs.retain(|a| !a.path.is_ident("indexed"));
+
+            fields.push(ast::EventStructField {
+                rust_name: ident.to_token_stream(),
+                name: ident.to_string(),
+                indexed,
+            });
+        }
+
+        // Name of the event defaults to the name of the structure
+        let name = match attrs.name() {
+            Some(name) => name.clone(),
+            None => self.ident.to_string(),
+        };
+
+        Ok(ast::EventStruct {
+            rust_name: self.ident.to_token_stream(),
+            name,
+            fields,
+        })
+    }
+}
+
+impl<'a> MacroParse<(Option<EventAttrs>, &'a mut TokenStream)> for syn::Item {
+    fn macro_parse(
+        self,
+        program: &mut ast::Program,
+        (attrs, tokens): (Option<EventAttrs>, &'a mut TokenStream),
+    ) -> Result<(), Diagnostic> {
+        match self {
+            syn::Item::Struct(mut s) => {
+                let attrs = attrs.unwrap_or_default();
+                program.event_structs.push((&mut s).convert(attrs)?);
+                s.to_tokens(tokens);
+            }
+            _ => {
+                return Err(Diagnostic::error(format!("{}", ExpectedStructure)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn struct_to_ast() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            pub struct Transfer {
+                #[indexed]
+                pub to: u64,
+                pub amount: u64
+            }
+        })
+        .unwrap();
+        let attrs: EventAttrs = syn::parse2(quote!(name = "token.transfer")).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        let parsed_struct = &program.event_structs[0];
+
+        assert_eq!(parsed_struct.name, "token.transfer");
+        assert_eq!(parsed_struct.fields.len(), 2);
+        assert!(parsed_struct.fields[0].indexed);
+        assert!(!parsed_struct.fields[1].indexed);
+        // Marker attribute is removed from the generated code
+        assert!(!tokens.to_string().contains("indexed"));
+    }
+
+    #[test]
+    fn tuple_struct() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            pub struct Transfer(u64, u64);
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(EventAttrs::default()), &mut tokens)) {
+            Err(err) => assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"structure with #[fvm_event] should have named fields, used as the keys of its entries.\" }"
+            ),
+            _ => panic!("event with unnamed fields should throw an error"),
+        }
+    }
+
+    #[test]
+    fn struct_with_empty_name() {
+        match syn::parse2::<EventAttrs>(quote!(name = "")) {
+            Err(err) => assert_eq!(err.to_string(), "event name can not be empty"),
+            _ => panic!("empty event name should throw an error"),
+        }
+    }
+}
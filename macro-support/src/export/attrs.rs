@@ -1,10 +1,20 @@
 use crate::utils::{generate_attr_getters, generate_attrs};
-use backend::export::attrs::{ExportAttr, Method};
+use backend::export::attrs::{Binding, CallerType, ExportAttr};
+use proc_macro2::Ident;
 use syn::parse::{Parse, ParseStream, Result};
 
 generate_attrs!(ExportAttrs, ExportAttr);
 
 generate_attr_getters!(
     ExportAttrs,
-    [(binding_method, ExportAttr::BindingMethod, Method),]
+    [
+        (binding_method, ExportAttr::BindingMethod, Binding),
+        (allow_reserved, ExportAttr::AllowReserved, bool),
+        (constructor, ExportAttr::Constructor, bool),
+        (payable, ExportAttr::Payable, bool),
+        (nonreentrant, ExportAttr::NonReentrant, bool),
+        (caller, ExportAttr::Caller, Ident),
+        (caller_in, ExportAttr::CallerIn, Ident),
+        (caller_type, ExportAttr::CallerType, Vec<CallerType>),
+    ]
 );
@@ -1,17 +1,22 @@
 //! Convert reads a source `TokenStream` to prepare the backend to generate custom code
 
+use std::convert::TryInto;
+
 use crate::utils::ConvertToAst;
-use backend::ast::Mutability;
-use backend::export::attrs::Method;
+use backend::ast::{CallerGuard, Mutability};
+use backend::export::attrs::{Binding, Method, RESERVED_METHOD_NUMBERS};
+use backend::export::frc42::{method_name_from_ident, CONSTRUCTOR_METHOD_NUMBER};
 use backend::{ast, Diagnostic};
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::{FnArg, GenericArgument, Pat, PathArguments, ReturnType, Type};
+use syn::{FnArg, GenericArgument, Pat, PathArguments, ReturnType, Signature, Type};
 
 use crate::export::attrs::ExportAttrs;
 use crate::export::error::Error::{
-    ExpectedBindingToNewVariable, GenericsOnEntryPoint, MissingBindingMethod,
-    UnexpectedArgReceiver, UnexpectedArgType, UnhandledType,
+    CallerGuardWithoutState, ExpectedBindingToNewVariable, GenericsOnEntryPoint,
+    InvalidConstructorBinding, InvalidConstructorSignature, InvalidNonReentrantSignature,
+    InvalidValueArgument, MissingBindingMethod, ReservedMethodNumber, UnexpectedArgReceiver,
+    UnexpectedArgType, UnhandledType, ValueOnNonPayable,
 };
 
 impl<'a> ConvertToAst<ExportAttrs> for &'a mut syn::ImplItemMethod {
@@ -41,34 +46,216 @@ impl<'a> ConvertToAst<ExportAttrs> for &'a mut syn::ImplItemMethod {
             None => Mutability::Pure,
         };
 
+        // Skip the receiver, if any, to get method arguments
+        let receiver_count = match mutability {
+            Mutability::Pure => 0,
+            _ => 1,
+        };
         let mut arguments = vec![];
-        for input in self.sig.inputs.iter().skip(1) {
+        for input in self.sig.inputs.iter().skip(receiver_count) {
             arguments.push(input.convert(())?)
         }
 
-        // Check if there is a returned value
-        let returns = match self.sig.output {
-            ReturnType::Default => false,
-            ReturnType::Type(_, _) => true,
+        // Check if there is a returned value, and if it is wrapped in a `Result`
+        let (returns, fallible, return_type) = match &self.sig.output {
+            ReturnType::Default => (false, false, None),
+            ReturnType::Type(_, ty) => match result_ok_type(ty.as_ref()) {
+                Some(Type::Tuple(t)) if t.elems.is_empty() => (false, true, None),
+                Some(ok_type) => (true, true, Some(ok_type.to_token_stream())),
+                None => (true, false, Some(ty.to_token_stream())),
+            },
+        };
+
+        let constructor = attrs.constructor().copied().unwrap_or(false);
+        if constructor {
+            // Constructor has no state to read from and should return the initial state
+            if !matches!(mutability, Mutability::Pure) || !returns {
+                return Err(Diagnostic::error(format!(
+                    "{}",
+                    InvalidConstructorSignature(self.sig.ident.to_string())
+                )));
+            }
+        }
+
+        // Trying to get a valid dispatch method and value, constructor being bound by default
+        let constructor_binding = Binding {
+            method: Method::Numeric(CONSTRUCTOR_METHOD_NUMBER),
+            literal: None,
+        };
+        let Binding {
+            method: binding_method,
+            literal: binding_literal,
+        } = match attrs.binding_method() {
+            Some(binding_method) => binding_method,
+            None if constructor => &constructor_binding,
+            None => {
+                return Err(Diagnostic::error(format!(
+                    "{}",
+                    MissingBindingMethod(self.sig.ident.to_string())
+                )))
+            }
         };
 
-        // Trying to get a valid dispatch method and value
-        let binding_method: &Method = attrs.binding_method().ok_or_else(|| {
-            Diagnostic::error(format!(
+        // If no method name was specified, derive it from the method identifier
+        let binding = match binding_method {
+            Method::Name(name) if name.is_empty() => {
+                Method::Name(method_name_from_ident(&self.sig.ident.to_string()))
+            }
+            _ => binding_method.clone(),
+        };
+
+        // Ensure that the binding can be resolved to a method number
+        let method_number: u64 = binding
+            .clone()
+            .try_into()
+            .map_err(|err| Diagnostic::error(format!("{}", err)))?;
+
+        if constructor && method_number != CONSTRUCTOR_METHOD_NUMBER {
+            return Err(Diagnostic::error(format!(
+                "{}",
+                InvalidConstructorBinding(self.sig.ident.to_string(), CONSTRUCTOR_METHOD_NUMBER)
+            )));
+        }
+
+        // Reserved method numbers can only be bound explicitly, constructors being bound to theirs
+        if !attrs.allow_reserved().copied().unwrap_or(false) {
+            if let Some((_, usage)) = RESERVED_METHOD_NUMBERS.iter().find(|(reserved, _)| {
+                *reserved == method_number
+                    && !(constructor && *reserved == CONSTRUCTOR_METHOD_NUMBER)
+            }) {
+                let error = ReservedMethodNumber(
+                    self.sig.ident.to_string(),
+                    method_number,
+                    usage.to_string(),
+                );
+                // Point to the literal binding the method number, when there is one
+                return Err(match binding_literal {
+                    Some(literal) => Diagnostic::spanned_error(&literal, format!("{}", error)),
+                    None => Diagnostic::error(format!("{}", error)),
+                });
+            }
+        }
+
+        // Guards on the caller, those relying on a state field requiring the state to be loaded
+        let mut caller_guards = vec![];
+        if let Some(field) = attrs.caller() {
+            caller_guards.push(CallerGuard::Field(field.clone()));
+        }
+        if let Some(field) = attrs.caller_in() {
+            caller_guards.push(CallerGuard::InField(field.clone()));
+        }
+        if matches!(mutability, Mutability::Pure) && !caller_guards.is_empty() {
+            return Err(Diagnostic::error(format!(
+                "{}",
+                CallerGuardWithoutState(self.sig.ident.to_string())
+            )));
+        }
+        if let Some(caller_types) = attrs.caller_type() {
+            caller_guards.push(CallerGuard::Types(caller_types.clone()));
+        }
+
+        // Value received can only be injected in entry points accepting it
+        let payable = attrs.payable().copied().unwrap_or(false);
+        if !payable
+            && arguments.iter().any(|argument: &ast::MethodArgument| {
+                matches!(argument.kind, ast::ArgumentKind::Value)
+            })
+        {
+            return Err(Diagnostic::error(format!(
+                "{}",
+                ValueOnNonPayable(self.sig.ident.to_string())
+            )));
+        }
+
+        // Only entry points writing on an existing state can lock it
+        let nonreentrant = attrs.nonreentrant().copied().unwrap_or(false);
+        if nonreentrant && (constructor || !matches!(mutability, Mutability::Write)) {
+            return Err(Diagnostic::error(format!(
                 "{}",
-                MissingBindingMethod(self.sig.ident.to_string())
-            ))
-        })?;
-
-        match binding_method {
-            Method::Numeric(value) => Ok(ast::ActorEntryPoint {
-                rust_name: self.sig.ident.to_token_stream(),
-                name: self.sig.ident.to_string(),
-                binding: Method::Numeric(*value),
-                mutability,
-                returns,
-                arguments,
-            }),
+                InvalidNonReentrantSignature(self.sig.ident.to_string())
+            )));
+        }
+
+        Ok(ast::ActorEntryPoint {
+            rust_name: self.sig.ident.to_token_stream(),
+            name: self.sig.ident.to_string(),
+            binding,
+            mutability,
+            // Constructed state is saved rather than returned
+            returns: returns && !constructor,
+            return_type: if constructor { None } else { return_type },
+            fallible,
+            constructor,
+            payable,
+            nonreentrant,
+            arguments,
+            caller_guards,
+        })
+    }
+}
+
+// Get the success type of a `Result` type, if the given type is one
+fn result_ok_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(p) = ty {
+        let segment = p.path.segments.last()?;
+        if segment.ident == "Result" {
+            if let PathArguments::AngleBracketed(b) = &segment.arguments {
+                if let Some(GenericArgument::Type(ok_type)) = b.args.first() {
+                    return Some(ok_type);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn is_token_amount(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => match p.path.segments.last() {
+            Some(segment) => segment.ident == "TokenAmount",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+// Whether the type is the `Context` of the SDK, named as `Context`, `actor::Context` or
+// `fvm_rs_sdk::actor::Context`. Other paths to a `Context` type are user payloads.
+fn is_context(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => {
+            if p.path
+                .segments
+                .iter()
+                .any(|segment| !matches!(segment.arguments, PathArguments::None))
+            {
+                return false;
+            }
+            let segments: Vec<String> = p
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            matches!(
+                segments
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .as_slice(),
+                ["Context"] | ["actor", "Context"] | ["fvm_rs_sdk", "actor", "Context"]
+            )
+        }
+        _ => false,
+    }
+}
+
+/// Remove the attributes marking injected arguments from the method signature, as they are only
+/// meaningful to our macros
+pub(crate) fn strip_argument_attrs(sig: &mut Signature) {
+    for input in sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = input {
+            pat_type.attrs.retain(|a| !a.path.is_ident("value"));
         }
     }
 }
@@ -91,10 +278,24 @@ impl<'a> ConvertToAst<()> for &'a FnArg {
 
                 let arg_type = pat_type.ty.as_ref().convert(())?;
 
+                // Arguments marked with `#[value]` receive the value sent along the message
+                let kind = if pat_type.attrs.iter().any(|a| a.path.is_ident("value")) {
+                    if !is_token_amount(pat_type.ty.as_ref()) {
+                        return Err(Diagnostic::error(format!("{}", InvalidValueArgument(name))));
+                    }
+                    ast::ArgumentKind::Value
+                } else if is_context(pat_type.ty.as_ref()) {
+                    // Context of the message is filled in rather than deserialized
+                    ast::ArgumentKind::Context
+                } else {
+                    ast::ArgumentKind::Payload
+                };
+
                 Ok(ast::MethodArgument {
                     name,
                     mutable,
                     arg_type,
+                    kind,
                 })
             }
             FnArg::Receiver(_) => Err(Diagnostic::error(format!("{}", UnexpectedArgReceiver))),
@@ -256,9 +457,12 @@ impl<'a> ConvertToAst<()> for &'a Type {
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
+
+    use crate::actor::attrs::ActorAttrs;
     use crate::utils::MacroParse;
-    use backend::ast::Mutability;
-    use backend::export::attrs::Method;
+    use backend::ast::{ArgumentKind, CallerGuard, Mutability};
+    use backend::export::attrs::{CallerType, Method};
     use proc_macro2::TokenStream;
     use quote::quote;
     use quote::ToTokens;
@@ -270,7 +474,7 @@ mod tests {
 
         (quote! {
             impl Actor {
-                #[fvm_export(method_num=1)]
+                #[fvm_export(method_num=1, allow_reserved)]
                 pub fn new() -> Self {
                     Actor {
                         count: 0
@@ -296,7 +500,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        item.macro_parse(&mut program, &mut tokens).unwrap();
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
 
         let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
         assert_eq!(actor_entry_points.len(), 3);
@@ -348,7 +553,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        if let Err(err) = item.macro_parse(&mut program, &mut tokens) {
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
             assert_eq!(
             err.to_token_stream().to_string(),
             "compile_error ! { \"expected attribute arguments in parentheses: #[fvm_export(...)]\" }"
@@ -381,7 +587,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        if let Err(err) = item.macro_parse(&mut program, &mut tokens) {
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
             assert_eq!(
                 err.to_token_stream().to_string(),
                 "compile_error ! { \"invalid 'method_num' value\" }"
@@ -414,7 +621,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        if let Err(err) = item.macro_parse(&mut program, &mut tokens) {
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
             assert_eq!(
                 err.to_token_stream().to_string(),
                 "compile_error ! { \"unknown attribute 'hello'\" }"
@@ -424,6 +632,330 @@ mod tests {
         }
     }
 
+    #[test]
+    fn name_binding() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_name = "Receive")]
+                pub fn receive(&mut self, value: u64) {
+                    self.count += value
+                }
+
+                #[fvm_export(method_name)]
+                pub fn balance_of(&self) -> u64 {
+                    self.count
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert_eq!(actor_entry_points.len(), 2);
+
+        assert_eq!(
+            actor_entry_points[0].binding,
+            Method::Name(String::from("Receive"))
+        );
+        let method_number: u64 = actor_entry_points[0].binding.clone().try_into().unwrap();
+        assert_eq!(method_number, 3726118371);
+
+        assert_eq!(
+            actor_entry_points[1].binding,
+            Method::Name(String::from("BalanceOf"))
+        );
+    }
+
+    #[test]
+    fn bad_method_name() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_name = "balance-of")]
+                pub fn balance_of(&self) -> u64 {
+                    self.count
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"method name 'balance-of' should start with an upper case letter or an underscore\" }"
+            )
+        } else {
+            panic!("method with #[fvm_export] and invalid method name should throw an error")
+        }
+    }
+
+    #[test]
+    fn reserved_method_number() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_num = 0)]
+                pub fn receive(&mut self) {
+                    self.count += 1
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"method number 0 bound to 'receive' is reserved for bare value transfers (METHOD_SEND). Use 'allow_reserved' to bind it anyway.\" }"
+            )
+        } else {
+            panic!(
+                "method with #[fvm_export] bound to a reserved method number should throw an error"
+            )
+        }
+    }
+
+    #[test]
+    fn reserved_constructor_method_number() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_num = 1)]
+                pub fn add(&mut self) {
+                    self.count += 1
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"method number 1 bound to 'add' is reserved for the constructor (METHOD_CONSTRUCTOR). Use 'allow_reserved' to bind it anyway.\" }"
+            )
+        } else {
+            panic!("non-constructor method bound to the constructor method number should throw an error")
+        }
+    }
+
+    #[test]
+    fn allowed_reserved_method_number() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_num = 0, allow_reserved)]
+                pub fn receive(&mut self) {
+                    self.count += 1
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert_eq!(actor_entry_points[0].binding, Method::Numeric(0));
+    }
+
+    #[test]
+    fn fallible_export() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn add(&mut self, value: u64) -> Result<(), ActorError> {
+                    self.count += value;
+                    Ok(())
+                }
+
+                #[fvm_export(method_num = 3)]
+                pub fn read(&self) -> std::result::Result<u64, ActorError> {
+                    Ok(self.count)
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+
+        assert!(actor_entry_points[0].fallible);
+        assert!(!actor_entry_points[0].returns);
+        assert!(actor_entry_points[0].return_type.is_none());
+
+        assert!(actor_entry_points[1].fallible);
+        assert!(actor_entry_points[1].returns);
+        assert_eq!(
+            actor_entry_points[1]
+                .return_type
+                .as_ref()
+                .unwrap()
+                .to_string(),
+            "u64"
+        );
+    }
+
+    #[test]
+    fn constructor() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(constructor)]
+                pub fn new(count: u64) -> Self {
+                    Actor {
+                        count
+                    }
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(actor_entry_points[0].constructor);
+        assert!(!actor_entry_points[0].returns);
+        assert_eq!(actor_entry_points[0].binding, Method::Numeric(1));
+        assert_eq!(actor_entry_points[0].arguments.len(), 1);
+        assert_eq!(
+            actor_entry_points[0].arguments[0].name,
+            String::from("count")
+        );
+    }
+
+    #[test]
+    fn constructor_with_receiver() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(constructor)]
+                pub fn new(&self) -> Self {
+                    Actor {
+                        count: self.count
+                    }
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"'new' can not be used as a constructor. Constructors should not take 'self' and should return the initial state.\" }"
+            )
+        } else {
+            panic!("constructor with receiver should throw an error")
+        }
+    }
+
+    #[test]
+    fn constructor_with_other_binding() {
+        // Mock impl token stream
+        let mut struct_token_stream = TokenStream::new();
+
+        (quote! {
+            impl Actor {
+                #[fvm_export(constructor, method_num = 2)]
+                pub fn new() -> Self {
+                    Actor {
+                        count: 0
+                    }
+                }
+            }
+        })
+        .to_tokens(&mut struct_token_stream);
+
+        // Parse struct and attrs
+        let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"constructor 'new' should be bound to method number 1\" }"
+            )
+        } else {
+            panic!("constructor bound to another method number should throw an error")
+        }
+    }
+
     #[test]
     fn generic_on_method() {
         // Mock impl token stream
@@ -445,7 +977,8 @@ mod tests {
         let mut tokens = TokenStream::new();
         let mut program = backend::ast::Program::default();
 
-        if let Err(err) = item.macro_parse(&mut program, &mut tokens) {
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
             assert_eq!(
                 err.to_token_stream().to_string(),
                 "compile_error ! { \"'mock' can not be used as an entry point. Methods with #[fvm_export] cannot have lifetime or type parameters.\" }"
@@ -454,4 +987,261 @@ mod tests {
             panic!("method with generics and #[fvm_export] should throw an error")
         }
     }
+
+    #[test]
+    fn caller_guards() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, caller = "owner", caller_type = [account, multisig])]
+                pub fn reset(&mut self) {
+                    self.count = 0
+                }
+
+                #[fvm_export(method_num = 3, caller_in = "admins")]
+                pub fn add(&mut self, value: u64) {
+                    self.count += value
+                }
+
+                #[fvm_export(method_num = 4, caller = "r#type")]
+                pub fn set_type(&mut self, value: u64) {
+                    self.count = value
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        match actor_entry_points[0].caller_guards.as_slice() {
+            [CallerGuard::Field(field), CallerGuard::Types(caller_types)] => {
+                assert_eq!(field, "owner");
+                assert_eq!(
+                    caller_types,
+                    &vec![CallerType::Account, CallerType::Multisig]
+                );
+            }
+            _ => panic!("method should be guarded by owner field and caller types"),
+        }
+        match actor_entry_points[1].caller_guards.as_slice() {
+            [CallerGuard::InField(field)] => assert_eq!(field, "admins"),
+            _ => panic!("method should be guarded by admins field"),
+        }
+        match actor_entry_points[2].caller_guards.as_slice() {
+            [CallerGuard::Field(field)] => assert_eq!(field, "r#type"),
+            _ => panic!("method should be guarded by raw type field"),
+        }
+    }
+
+    #[test]
+    fn caller_guards_without_state() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, caller = "owner")]
+                pub fn ping() -> u64 {
+                    1
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"'ping' can not check its caller against a state field. Methods guarded with 'caller' or 'caller_in' should take 'self'.\" }"
+            )
+        } else {
+            panic!("pure method guarded by a state field should throw an error")
+        }
+    }
+
+    #[test]
+    fn unknown_caller_type() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, caller_type = [wallet])]
+                pub fn ping() -> u64 {
+                    1
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"unknown actor type 'wallet'\" }"
+            )
+        } else {
+            panic!("unknown caller type should throw an error")
+        }
+    }
+
+    #[test]
+    fn payable_with_value() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, payable)]
+                pub fn deposit(&mut self, to: u64, #[value] amount: TokenAmount) {
+                    self.deposits += amount
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(actor_entry_points[0].payable);
+        assert!(matches!(
+            actor_entry_points[0].arguments[0].kind,
+            ArgumentKind::Payload
+        ));
+        assert!(matches!(
+            actor_entry_points[0].arguments[1].kind,
+            ArgumentKind::Value
+        ));
+        // Marker attribute is removed from the generated code
+        assert!(!tokens.to_string().contains("# [value]"));
+    }
+
+    #[test]
+    fn value_on_non_payable() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn deposit(&mut self, #[value] amount: TokenAmount) {
+                    self.deposits += amount
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        if let Err(err) = item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+        {
+            assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"'deposit' can not receive value. Use 'payable' to accept value sent along the message.\" }"
+            )
+        } else {
+            panic!("value injected in a method that is not payable should throw an error")
+        }
+    }
+
+    #[test]
+    fn context_argument() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn add(&mut self, ctx: fvm_rs_sdk::actor::Context, value: u64) {
+                    self.count += value
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(matches!(
+            actor_entry_points[0].arguments[0].kind,
+            ArgumentKind::Context
+        ));
+        assert!(actor_entry_points[0].arguments[1].is_payload());
+    }
+
+    #[test]
+    fn payload_named_context() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2)]
+                pub fn add(&mut self, ctx: actor::Context, params: crate::types::Context) {
+                    self.count += params.value
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(matches!(
+            actor_entry_points[0].arguments[0].kind,
+            ArgumentKind::Context
+        ));
+        assert!(actor_entry_points[0].arguments[1].is_payload());
+    }
+
+    #[test]
+    fn nonreentrant_entry_point() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, nonreentrant)]
+                pub fn withdraw(&mut self, amount: u64) {
+                    self.balance -= amount
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens))
+            .unwrap();
+
+        let actor_entry_points = &program.actor_implementation.unwrap().entry_points;
+        assert!(actor_entry_points[0].nonreentrant);
+    }
+
+    #[test]
+    fn nonreentrant_without_mutable_state() {
+        let item = syn::parse2::<syn::Item>(quote! {
+            impl Actor {
+                #[fvm_export(method_num = 2, nonreentrant)]
+                pub fn balance(&self) -> u64 {
+                    self.balance
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(ActorAttrs::default()), &mut tokens)) {
+            Err(err) => assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"'balance' can not be non-reentrant. Non-reentrant entry points should take '&mut self' to lock the state.\" }"
+            ),
+            Ok(_) => panic!("non-reentrant entry point reading the state should throw an error"),
+        }
+    }
 }
@@ -19,4 +19,25 @@ pub enum Error {
     /// This error is thrown when the pattern for argument is not a biding to a new variable
     #[error("expected binding to variable when parsing method arguments.")]
     ExpectedBindingToNewVariable,
+    /// This error is thrown when an entry point is bound to a method number reserved by the Filecoin conventions
+    #[error("method number {1} bound to '{0}' is reserved for {2}. Use 'allow_reserved' to bind it anyway.")]
+    ReservedMethodNumber(String, u64, String),
+    /// This error is thrown when a constructor has a receiver or does not return the initial state
+    #[error("'{0}' can not be used as a constructor. Constructors should not take 'self' and should return the initial state.")]
+    InvalidConstructorSignature(String),
+    /// This error is thrown when a constructor is bound to another method number than the constructor one
+    #[error("constructor '{0}' should be bound to method number {1}")]
+    InvalidConstructorBinding(String, u64),
+    /// This error is thrown when a caller guard relying on a state field is set on a method without state
+    #[error("'{0}' can not check its caller against a state field. Methods guarded with 'caller' or 'caller_in' should take 'self'.")]
+    CallerGuardWithoutState(String),
+    /// This error is thrown when an argument marked with `#[value]` is not a token amount
+    #[error("argument '{0}' marked with #[value] should be of type 'TokenAmount'")]
+    InvalidValueArgument(String),
+    /// This error is thrown when the value received is injected in an entry point that is not payable
+    #[error("'{0}' can not receive value. Use 'payable' to accept value sent along the message.")]
+    ValueOnNonPayable(String),
+    /// This error is thrown when a non-reentrant entry point does not write on an existing state
+    #[error("'{0}' can not be non-reentrant. Non-reentrant entry points should take '&mut self' to lock the state.")]
+    InvalidNonReentrantSignature(String),
 }
@@ -0,0 +1,13 @@
+#[derive(thiserror::Error, Debug)]
+/// Errors related to actor's interface parsing.
+pub enum Error {
+    /// This error is thrown when procedural macro is not used on a trait
+    #[error("#[fvm_interface] should be used with a trait.")]
+    ExpectedTrait,
+    /// This error is thrown when a trait is declared with generics
+    #[error("trait with #[fvm_interface] cannot have lifetime or type parameters.")]
+    GenericsOnTrait,
+    /// This error is thrown when an implementation misses an entry point declared by its interface
+    #[error("method '{0}' of interface '{1}' is not implemented")]
+    MissingInterfaceMethod(String, String),
+}
@@ -0,0 +1,309 @@
+//! Parser reads a source `TokenStream` to prepare the backend to generate custom code
+
+use backend::ast::ActorEntryPoint;
+use backend::{ast, Diagnostic};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream, Result as SynResult};
+use syn::{FnArg, ImplItem, TraitItem};
+
+use crate::actor::attrs::ActorAttrs;
+use crate::actor::parser::{check_duplicate_bindings, export_attrs, strip_impl_argument_attrs};
+use crate::export::convert::strip_argument_attrs;
+use crate::interface::error::Error::{GenericsOnTrait, MissingInterfaceMethod};
+use crate::utils::{ConvertToAst, MacroParse};
+
+impl<'a> ConvertToAst<()> for &'a mut syn::ItemTrait {
+    type Target = ast::ActorInterface;
+
+    fn convert(self, _: ()) -> Result<Self::Target, Diagnostic> {
+        // Not handling generics on interface
+        if !self.generics.params.is_empty() {
+            return Err(Diagnostic::error(format!("{}", GenericsOnTrait)));
+        }
+
+        // Initialize entry points
+        let mut entry_points: Vec<ActorEntryPoint> = vec![];
+
+        for item in &self.items {
+            if let TraitItem::Method(m) = item {
+                // Parse export attributes
+                let export_attrs = match export_attrs(&m.attrs)? {
+                    Some(export_attrs) => export_attrs,
+                    None => continue,
+                };
+
+                // Generate ast entry point
+                let entry_point: ActorEntryPoint = (&mut trait_method(m)).convert(export_attrs)?;
+                entry_points.push(entry_point);
+            }
+        }
+
+        check_duplicate_bindings(&entry_points)?;
+
+        Ok(ast::ActorInterface {
+            rust_name: self.ident.clone(),
+            name: self.ident.to_string(),
+            entry_points,
+            definition: self.to_token_stream(),
+        })
+    }
+}
+
+// Trait method as an implementation method, to convert it to an entry point
+fn trait_method(method: &syn::TraitItemMethod) -> syn::ImplItemMethod {
+    syn::ImplItemMethod {
+        attrs: method.attrs.clone(),
+        vis: syn::Visibility::Inherited,
+        defaultness: None,
+        sig: method.sig.clone(),
+        block: method
+            .default
+            .clone()
+            .unwrap_or_else(|| syn::parse_quote!({})),
+    }
+}
+
+// Injected arguments are declared by the interface, mark them as such on the implementation
+fn inherit_argument_attrs(declared: &syn::Signature, implemented: &mut syn::Signature) {
+    for (declared, implemented) in declared.inputs.iter().zip(implemented.inputs.iter_mut()) {
+        if let (FnArg::Typed(declared), FnArg::Typed(implemented)) = (declared, implemented) {
+            let is_value = |a: &syn::Attribute| a.path.is_ident("value");
+            if declared.attrs.iter().any(is_value) && !implemented.attrs.iter().any(is_value) {
+                implemented.attrs.push(syn::parse_quote!(#[value]));
+            }
+        }
+    }
+}
+
+impl<'a> MacroParse<&'a mut TokenStream> for syn::ItemTrait {
+    fn macro_parse(
+        mut self,
+        program: &mut ast::Program,
+        tokens: &'a mut TokenStream,
+    ) -> Result<(), Diagnostic> {
+        program.actor_interface = Some((&mut self).convert(())?);
+        for item in self.items.iter_mut() {
+            if let TraitItem::Method(m) = item {
+                strip_argument_attrs(&mut m.sig);
+            }
+        }
+        self.to_tokens(tokens);
+
+        Ok(())
+    }
+}
+
+/// An interface along with an implementation of it, as passed by the macro generated for the
+/// interface
+pub(crate) struct InterfaceImplementation {
+    item_trait: syn::ItemTrait,
+    item_impl: syn::ItemImpl,
+}
+
+impl Parse for InterfaceImplementation {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        Ok(InterfaceImplementation {
+            item_trait: input.parse()?,
+            item_impl: input.parse()?,
+        })
+    }
+}
+
+impl<'a> MacroParse<&'a mut TokenStream> for InterfaceImplementation {
+    fn macro_parse(
+        mut self,
+        program: &mut ast::Program,
+        tokens: &'a mut TokenStream,
+    ) -> Result<(), Diagnostic> {
+        let interface = (&mut self.item_trait).convert(())?;
+        let mut implementation = (&mut self.item_impl).convert(())?;
+
+        // Attributes of the `#[fvm_actor]` of the implementation are passed along with it
+        let is_actor_attr = |attr: &syn::Attribute| attr.path.is_ident("fvm_actor");
+        for attr in self
+            .item_impl
+            .attrs
+            .iter()
+            .filter(|attr| is_actor_attr(attr))
+        {
+            let actor_attrs = attr.parse_args::<ActorAttrs>()?;
+            implementation.client_feature = actor_attrs.client_feature().cloned();
+        }
+        self.item_impl.attrs.retain(|attr| !is_actor_attr(attr));
+
+        // Entry points are bound as declared by the interface, with the signature of their
+        // implementation
+        let mut entry_points: Vec<ActorEntryPoint> = vec![];
+        for item in &self.item_trait.items {
+            if let TraitItem::Method(m) = item {
+                let export_attrs = match export_attrs(&m.attrs)? {
+                    Some(export_attrs) => export_attrs,
+                    None => continue,
+                };
+
+                let implemented = self.item_impl.items.iter().find_map(|item| match item {
+                    ImplItem::Method(implemented) if implemented.sig.ident == m.sig.ident => {
+                        Some(implemented.clone())
+                    }
+                    _ => None,
+                });
+                let entry_point = match implemented {
+                    Some(mut implemented) => {
+                        inherit_argument_attrs(&m.sig, &mut implemented.sig);
+                        (&mut implemented).convert(export_attrs)?
+                    }
+                    // Methods with a default body do not have to be implemented
+                    None if m.default.is_some() => (&mut trait_method(m)).convert(export_attrs)?,
+                    None => {
+                        return Err(Diagnostic::error(format!(
+                            "{}",
+                            MissingInterfaceMethod(m.sig.ident.to_string(), interface.name.clone())
+                        )))
+                    }
+                };
+                entry_points.push(entry_point);
+            }
+        }
+        implementation.entry_points = entry_points;
+
+        program.actor_implementation = Some(implementation);
+        strip_impl_argument_attrs(&mut self.item_impl);
+        self.item_impl.to_tokens(tokens);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use std::convert::TryInto;
+
+    use super::*;
+
+    #[test]
+    fn trait_to_ast() {
+        let item = syn::parse2::<syn::ItemTrait>(quote! {
+            pub trait Token {
+                #[fvm_export(method_num = 2)]
+                fn balance(&self, owner: u64) -> u64;
+
+                #[fvm_export(method_name)]
+                fn transfer(&mut self, to: u64, amount: u64);
+
+                fn helper(&self) -> u64 {
+                    0
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, &mut tokens).unwrap();
+
+        let interface = program.actor_interface.unwrap();
+        assert_eq!(interface.name, "Token");
+        assert_eq!(interface.entry_points.len(), 2);
+        assert_eq!(interface.entry_points[0].name, "balance");
+        assert_eq!(interface.entry_points[0].arguments.len(), 1);
+        assert_eq!(interface.entry_points[1].name, "transfer");
+    }
+
+    #[test]
+    fn implementation_inherits_bindings() {
+        let implementation = syn::parse2::<InterfaceImplementation>(quote! {
+            pub trait Token {
+                #[fvm_export(method_num = 2)]
+                fn balance(&self, owner: u64) -> u64;
+
+                #[fvm_export(method_num = 3)]
+                fn supply(&self) -> u64 {
+                    0
+                }
+            }
+
+            impl Token for State {
+                fn balance(&self, owner: u64) -> u64 {
+                    self.balances[owner]
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        implementation
+            .macro_parse(&mut program, &mut tokens)
+            .unwrap();
+
+        let actor_implementation = program.actor_implementation.unwrap();
+        assert_eq!(actor_implementation.name, "State");
+        assert_eq!(actor_implementation.entry_points.len(), 2);
+        let method_number: u64 = actor_implementation.entry_points[0]
+            .binding
+            .clone()
+            .try_into()
+            .unwrap();
+        assert_eq!(method_number, 2);
+        assert_eq!(actor_implementation.entry_points[1].name, "supply");
+    }
+
+    #[test]
+    fn implementation_with_client_feature() {
+        let implementation = syn::parse2::<InterfaceImplementation>(quote! {
+            pub trait Token {
+                #[fvm_export(method_num = 2)]
+                fn balance(&self, owner: u64) -> u64;
+            }
+
+            #[fvm_actor(client_feature = "client")]
+            impl Token for State {
+                fn balance(&self, owner: u64) -> u64 {
+                    self.balances[owner]
+                }
+            }
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        implementation
+            .macro_parse(&mut program, &mut tokens)
+            .unwrap();
+
+        assert_eq!(
+            program.actor_implementation.unwrap().client_feature,
+            Some(String::from("client"))
+        );
+        assert!(!tokens.to_string().contains("fvm_actor"));
+    }
+
+    #[test]
+    fn implementation_missing_method() {
+        let implementation = syn::parse2::<InterfaceImplementation>(quote! {
+            pub trait Token {
+                #[fvm_export(method_num = 2)]
+                fn balance(&self, owner: u64) -> u64;
+            }
+
+            impl Token for State {}
+        })
+        .unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match implementation.macro_parse(&mut program, &mut tokens) {
+            Err(err) => assert_eq!(
+                err.to_token_stream().to_string(),
+                "compile_error ! { \"method 'balance' of interface 'Token' is not implemented\" }"
+            ),
+            _ => panic!("implementation missing a method of its interface should throw an error"),
+        }
+    }
+}
@@ -6,14 +6,20 @@ extern crate proc_macro2;
 extern crate quote;
 extern crate syn;
 
+use crate::actor::attrs::ActorAttrs;
+use crate::event::attrs::EventAttrs;
+use crate::interface::error::Error::ExpectedTrait;
+use crate::interface::parser::InterfaceImplementation;
 use crate::payload::attrs::PayloadAttrs;
 use backend::Diagnostic;
 use proc_macro2::TokenStream;
 
-use crate::state::attrs::StateAttrs;
+use crate::state::attrs::{MigrateAttrs, StateAttrs};
 
 mod actor;
+mod event;
 mod export;
+mod interface;
 mod payload;
 mod state;
 mod utils;
@@ -22,6 +28,9 @@ pub enum MacroType {
     State,
     Actor,
     Payload,
+    Interface,
+    Event,
+    Migration,
 }
 
 /// Takes the parsed input from a procedural macro and returns the generated bindings
@@ -51,9 +60,25 @@ pub fn expand(
 
             item.macro_parse(&mut program, (Some(attrs), &mut tokens))?;
         }
-        _ => {
-            item.macro_parse(&mut program, &mut tokens)?;
+        MacroType::Event => {
+            let attrs: EventAttrs = syn::parse2(attr)?;
+
+            item.macro_parse(&mut program, (Some(attrs), &mut tokens))?;
+        }
+        MacroType::Migration => {
+            let attrs: MigrateAttrs = syn::parse2(attr)?;
+
+            item.macro_parse(&mut program, (Some(attrs), &mut tokens))?;
         }
+        MacroType::Actor => {
+            let attrs: ActorAttrs = syn::parse2(attr)?;
+
+            item.macro_parse(&mut program, (Some(attrs), &mut tokens))?;
+        }
+        MacroType::Interface => match item {
+            syn::Item::Trait(item_trait) => item_trait.macro_parse(&mut program, &mut tokens)?,
+            _ => return Err(Diagnostic::error(format!("{}", ExpectedTrait))),
+        },
     }
 
     // Second step is to generate code custom tokens based on custom AST structures & append it to
@@ -62,3 +87,20 @@ pub fn expand(
 
     Ok(tokens)
 }
+
+/// Takes an interface along with an implementation of it, as passed by the macro generated for the
+/// interface, and returns the generated bindings of the implementation
+pub fn expand_interface_implementation(input: TokenStream) -> Result<TokenStream, Diagnostic> {
+    use crate::utils::MacroParse;
+    use backend::TryToTokens;
+
+    let implementation = syn::parse2::<InterfaceImplementation>(input)?;
+
+    let mut tokens = TokenStream::new();
+    let mut program = backend::ast::Program::default();
+
+    implementation.macro_parse(&mut program, &mut tokens)?;
+    program.try_to_tokens(&mut tokens)?;
+
+    Ok(tokens)
+}
@@ -7,4 +7,9 @@ pub enum Error {
     /// This error is thrown when procedural macro is not used on a structure
     #[error("#[fvm_payload] should be used with a structure.")]
     ExpectedStructure,
+    /// This error is thrown when a field holds a map keyed by a type that is not a string
+    #[error(
+        "field '{0}' can not be keyed by '{1}', as the keys of an IPLD map have to be strings."
+    )]
+    NonStringMapKey(String, String),
 }
@@ -2,11 +2,11 @@
 
 use crate::payload::attrs::PayloadAttrs;
 use backend::payload::attrs::Codec;
-use backend::{ast, Diagnostic};
+use backend::{ast, schema, Diagnostic};
 use proc_macro2::TokenStream;
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 
-use crate::payload::error::Error::{ExpectedStructure, GenericsOnStructure};
+use crate::payload::error::Error::{ExpectedStructure, GenericsOnStructure, NonStringMapKey};
 use crate::utils::{ConvertToAst, MacroParse};
 
 impl<'a> ConvertToAst<PayloadAttrs> for &'a mut syn::ItemStruct {
@@ -18,6 +18,27 @@ impl<'a> ConvertToAst<PayloadAttrs> for &'a mut syn::ItemStruct {
             return Err(Diagnostic::error(format!("{}", GenericsOnStructure)));
         }
 
+        // All fields are serialized, whatever their visibility
+        let mut fields = Vec::new();
+        for (i, field) in self.fields.iter().enumerate() {
+            // Derive field name from ident
+            let (name, token_stream) = match &field.ident {
+                Some(ident) => (ident.to_string(), ident.to_token_stream()),
+                None => (i.to_string(), quote!(#i).to_token_stream()),
+            };
+
+            if let Some(key) = schema::non_string_map_key(&field.ty) {
+                return Err(Diagnostic::error(format!("{}", NonStringMapKey(name, key))));
+            }
+
+            fields.push(ast::PayloadStructField {
+                rust_name: token_stream,
+                name,
+                ty: field.ty.clone(),
+                bytes: schema::serialized_as_bytes(&field.attrs),
+            });
+        }
+
         // Attrs assignment
         let codec = match attrs.codec() {
             Some(codec) => codec.clone(),
@@ -28,6 +49,7 @@ impl<'a> ConvertToAst<PayloadAttrs> for &'a mut syn::ItemStruct {
         Ok(ast::PayloadStruct {
             rust_name: self.ident.to_token_stream(),
             name: self.ident.to_string(),
+            fields,
             codec,
         })
     }
@@ -92,6 +114,8 @@ mod tests {
 
         assert_eq!(parsed_struct.name, "MockStruct");
         assert_eq!(parsed_struct.rust_name.to_string(), parsed_struct.name);
+        assert_eq!(parsed_struct.fields.len(), 1);
+        assert_eq!(parsed_struct.fields[0].name, "count");
         assert_eq!(parsed_struct.codec, Codec::DagCbor);
     }
 
@@ -136,6 +160,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn no_non_string_map_key() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub amounts: HashMap<u64, u64>
+            }
+        };
+        let attrs: PayloadAttrs = syn::parse2(quote! {}).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(diagnostic) => {
+                let res_panic = std::panic::catch_unwind(|| diagnostic.panic());
+                match res_panic {
+                    Err(err) => match err.downcast::<String>() {
+                        Ok(panic_msg_box) => {
+                            assert_eq!(panic_msg_box.as_str(), "field 'amounts' can not be keyed by 'u64', as the keys of an IPLD map have to be strings.");
+                        }
+                        Err(_) => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            _ => panic!("parse result should be error when a map is keyed by integers"),
+        }
+    }
+
     #[test]
     fn struct_with_codec_attr() {
         // Mock struct token stream
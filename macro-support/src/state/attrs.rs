@@ -1,9 +1,23 @@
 use crate::utils::{generate_attr_getters, generate_attrs};
-use backend::state::attrs::{Codec, StateAttr};
+use backend::state::attrs::{Codec, HashFunction, MigrateAttr, StateAttr, Version};
 use syn::parse::{Parse, ParseStream, Result};
 
 // Parsed attributes from a `#[fvm_state(..)]`.
 generate_attrs!(StateAttrs, StateAttr);
 
 // Generate getters to retrieve attributes values
-generate_attr_getters!(StateAttrs, [(codec, StateAttr::Codec, Codec),]);
+generate_attr_getters!(
+    StateAttrs,
+    [
+        (codec, StateAttr::Codec, Codec),
+        (hash, StateAttr::Hash, HashFunction),
+        (version, StateAttr::Version, Version),
+        (lazy, StateAttr::Lazy, bool),
+    ]
+);
+
+// Parsed attributes from a `#[fvm_migrate(..)]`.
+generate_attrs!(MigrateAttrs, MigrateAttr);
+
+// Generate getters to retrieve attributes values
+generate_attr_getters!(MigrateAttrs, [(from, MigrateAttr::From, u64),]);
@@ -7,4 +7,41 @@ pub enum Error {
     /// This error is thrown when procedural macro is not used on a structure
     #[error("#[fvm_state] should be used with a structure.")]
     ExpectedStructure,
+    /// This error is thrown when a raw state object is versioned
+    #[error(
+        "#[fvm_state(version = ..)] is only available for states stored with a DAG-CBOR codec."
+    )]
+    VersionOnRawState,
+    /// This error is thrown when a field is marked with `#[lazy]` on a state that is not lazy
+    #[error(
+        "field '{0}' can not be lazy. Use #[fvm_state(lazy)] to store fields in their own block."
+    )]
+    LazyFieldOnEagerState(String),
+    /// This error is thrown when a field marked with `#[lazy]` has no name to derive its accessors from
+    #[error("fields marked with #[lazy] should be named, their accessors being named after them.")]
+    UnnamedLazyField,
+    /// This error is thrown when a raw state object is lazy
+    #[error("#[fvm_state(lazy)] is only available for states stored with a DAG-CBOR codec.")]
+    LazyRawState,
+    /// This error is thrown when a lazy state has no field marked with `#[lazy]`
+    #[error(
+        "#[fvm_state(lazy)] expects fields to store in their own block to be marked with #[lazy]."
+    )]
+    MissingLazyField,
+    /// This error is thrown when a field holds a map keyed by a type that is not a string
+    #[error(
+        "field '{0}' can not be keyed by '{1}', as the keys of an IPLD map have to be strings."
+    )]
+    NonStringMapKey(String, String),
+    /// This error is thrown when procedural macro is not used on a function
+    #[error("#[fvm_migrate] should be used with a function of an implementation of the state.")]
+    ExpectedFunction,
+    /// This error is thrown when the version migrated from is not specified
+    #[error(
+        "#[fvm_migrate] expects the version it migrates from, as in #[fvm_migrate(from = 1)]."
+    )]
+    MissingMigrationVersion,
+    /// This error is thrown when a migration hook does not take a previous layout to return the next one
+    #[error("#[fvm_migrate] function should take the previous layout of the state as its only argument and return the next one.")]
+    InvalidMigrationSignature,
 }
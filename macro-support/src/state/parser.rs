@@ -1,12 +1,16 @@
 //! Parser reads a source `TokenStream` to prepare the backend to generate custom code
 
-use backend::state::attrs::Codec;
-use backend::{ast, Diagnostic};
+use backend::state::attrs::{Codec, HashFunction};
+use backend::{ast, schema, Diagnostic};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
-use crate::state::attrs::StateAttrs;
-use crate::state::error::Error::{ExpectedStructure, GenericsOnStructure};
+use crate::state::attrs::{MigrateAttrs, StateAttrs};
+use crate::state::error::Error::{
+    ExpectedFunction, ExpectedStructure, GenericsOnStructure, InvalidMigrationSignature,
+    LazyFieldOnEagerState, LazyRawState, MissingLazyField, MissingMigrationVersion,
+    NonStringMapKey, UnnamedLazyField, VersionOnRawState,
+};
 use crate::utils::{ConvertToAst, MacroParse};
 
 impl<'a> ConvertToAst<StateAttrs> for &'a mut syn::ItemStruct {
@@ -18,27 +22,60 @@ impl<'a> ConvertToAst<StateAttrs> for &'a mut syn::ItemStruct {
             return Err(Diagnostic::error(format!("{}", GenericsOnStructure)));
         }
 
+        let lazy = attrs.lazy().copied().unwrap_or(false);
+
         // When handling struct, first create fields objects
         let mut fields = Vec::new();
+        let mut serialized_fields = Vec::new();
         for (i, field) in self.fields.iter_mut().enumerate() {
-            // Fields visibility has to be public to be taken into account
-            match field.vis {
-                syn::Visibility::Public(..) => {}
-                _ => continue,
-            }
-
             // Derive field name from ident
             let (name, token_stream) = match &field.ident {
                 Some(ident) => (ident.to_string(), ident.to_token_stream().clone()),
                 None => (i.to_string(), quote!(#i).to_token_stream()),
             };
 
-            fields.push(ast::StateStructField {
+            // Fields marked with `#[lazy]` are wrapped to be stored in their own block
+            let lazy_field = field.attrs.iter().any(|a| a.path.is_ident("lazy"));
+            field.attrs.retain(|a| !a.path.is_ident("lazy"));
+            let ty = field.ty.clone();
+            if lazy_field {
+                if !lazy {
+                    return Err(Diagnostic::error(format!(
+                        "{}",
+                        LazyFieldOnEagerState(name)
+                    )));
+                }
+                if field.ident.is_none() {
+                    return Err(Diagnostic::error(format!("{}", UnnamedLazyField)));
+                }
+                field.ty = syn::parse_quote!(fvm_rs_sdk::state::Lazy<#ty>);
+            }
+
+            // Fields marked with `#[collection]` hold a collection stored in its own blocks, their
+            // entries being outside of the state block
+            let collection = field.attrs.iter().any(|a| a.path.is_ident("collection"));
+            field.attrs.retain(|a| !a.path.is_ident("collection"));
+            if !collection {
+                if let Some(key) = schema::non_string_map_key(&ty) {
+                    return Err(Diagnostic::error(format!("{}", NonStringMapKey(name, key))));
+                }
+            }
+
+            let state_field = ast::StateStructField {
                 rust_name: token_stream,
                 name,
                 struct_name: self.ident.clone(),
-                ty: field.ty.clone(),
-            });
+                ty,
+                lazy: lazy_field,
+                collection,
+                bytes: schema::serialized_as_bytes(&field.attrs),
+            };
+
+            // All fields are serialized, but only public ones are taken into account otherwise
+            serialized_fields.push(state_field.clone());
+            if let syn::Visibility::Public(..) = field.vis {
+                fields.push(state_field);
+            }
         }
 
         // Attrs assignment
@@ -46,17 +83,93 @@ impl<'a> ConvertToAst<StateAttrs> for &'a mut syn::ItemStruct {
             Some(codec) => codec.clone(),
             None => Codec::default(),
         };
+        let hash = match attrs.hash() {
+            Some(hash) => hash.clone(),
+            None => HashFunction::default(),
+        };
+        // Raw state objects handle their own conversion, so their layout can not be tagged
+        let version = attrs.version().copied();
+        if version.is_some() && codec == Codec::Raw {
+            return Err(Diagnostic::error(format!("{}", VersionOnRawState)));
+        }
+        if lazy {
+            // Raw state objects handle their own conversion, so their fields can not be linked
+            if codec == Codec::Raw {
+                return Err(Diagnostic::error(format!("{}", LazyRawState)));
+            }
+            if !serialized_fields.iter().any(|field| field.lazy) {
+                return Err(Diagnostic::error(format!("{}", MissingLazyField)));
+            }
+        }
 
         // Generate the AST object for the Struct
         Ok(ast::StateStruct {
             rust_name: self.ident.to_token_stream(),
             name: self.ident.to_string(),
             fields,
+            serialized_fields,
             codec,
+            hash,
+            version,
         })
     }
 }
 
+impl<'a> ConvertToAst<MigrateAttrs> for &'a syn::ItemFn {
+    type Target = ast::MigrationHook;
+
+    fn convert(self, attrs: MigrateAttrs) -> Result<Self::Target, Diagnostic> {
+        let from = match attrs.from() {
+            Some(from) => *from,
+            None => return Err(Diagnostic::error(format!("{}", MissingMigrationVersion))),
+        };
+
+        // The hook takes the previous layout by value and returns the next one
+        if !self.sig.generics.params.is_empty() || self.sig.inputs.len() != 1 {
+            return Err(Diagnostic::error(format!("{}", InvalidMigrationSignature)));
+        }
+        let previous = match self.sig.inputs.first() {
+            Some(syn::FnArg::Typed(pat_type)) => (*pat_type.ty).clone(),
+            _ => return Err(Diagnostic::error(format!("{}", InvalidMigrationSignature))),
+        };
+        let next = match &self.sig.output {
+            syn::ReturnType::Type(_, ty) => (**ty).clone(),
+            syn::ReturnType::Default => {
+                return Err(Diagnostic::error(format!("{}", InvalidMigrationSignature)))
+            }
+        };
+
+        Ok(ast::MigrationHook {
+            rust_name: self.sig.ident.clone(),
+            from,
+            previous,
+            next,
+        })
+    }
+}
+
+impl<'a> MacroParse<(Option<MigrateAttrs>, &'a mut TokenStream)> for syn::Item {
+    fn macro_parse(
+        self,
+        program: &mut ast::Program,
+        (attrs, tokens): (Option<MigrateAttrs>, &'a mut TokenStream),
+    ) -> Result<(), Diagnostic> {
+        match self {
+            // Handles functions, expected in an implementation of the state
+            syn::Item::Fn(f) => {
+                let attrs = attrs.unwrap_or_default();
+                program.migration_hooks.push((&f).convert(attrs)?);
+                f.to_tokens(tokens);
+            }
+            _ => {
+                return Err(Diagnostic::error(format!("{}", ExpectedFunction)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl<'a> MacroParse<(Option<StateAttrs>, &'a mut TokenStream)> for syn::Item {
     fn macro_parse(
         self,
@@ -208,6 +321,7 @@ mod tests {
 
         assert_eq!(parsed_struct.fields.len(), 1usize);
         assert_eq!(parsed_field.name, "count");
+        assert_eq!(parsed_struct.serialized_fields.len(), 2usize);
     }
 
     #[test]
@@ -244,6 +358,42 @@ mod tests {
         assert_eq!(parsed_struct.codec, Codec::DagCbor)
     }
 
+    #[test]
+    fn struct_with_other_codecs() {
+        for (codec, expected) in [("dag-cbor-map", Codec::DagCborMap), ("raw", Codec::Raw)] {
+            // Mock struct token stream
+            let mut struct_token_stream = TokenStream::new();
+
+            (quote! {
+                pub struct MockStruct {
+                    pub count: u64
+                }
+            })
+            .to_tokens(&mut struct_token_stream);
+
+            // Mock codec attr
+            let mut attrs_token_stream = TokenStream::new();
+            (quote! {
+                codec = #codec
+            })
+            .to_tokens(&mut attrs_token_stream);
+
+            // Parse struct and attrs
+            let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+            let attrs: StateAttrs = syn::parse2(attrs_token_stream).unwrap();
+
+            let mut tokens = TokenStream::new();
+            let mut program = backend::ast::Program::default();
+
+            item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+                .unwrap();
+
+            let parsed_struct = &program.state_structs[0];
+
+            assert_eq!(parsed_struct.codec, expected)
+        }
+    }
+
     #[test]
     fn struct_with_unknown_attr() {
         // Mock no attrs
@@ -276,6 +426,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn struct_with_hash_attr() {
+        for (hash, expected) in [
+            ("blake2b-256", HashFunction::Blake2b256),
+            ("sha2-256", HashFunction::Sha2_256),
+            ("keccak-256", HashFunction::Keccak256),
+        ] {
+            // Mock struct token stream
+            let mut struct_token_stream = TokenStream::new();
+
+            (quote! {
+                pub struct MockStruct {
+                    pub count: u64
+                }
+            })
+            .to_tokens(&mut struct_token_stream);
+
+            // Mock hash attr
+            let mut attrs_token_stream = TokenStream::new();
+            (quote! {
+                hash = #hash
+            })
+            .to_tokens(&mut attrs_token_stream);
+
+            // Parse struct and attrs
+            let item = syn::parse2::<syn::Item>(struct_token_stream).unwrap();
+            let attrs: StateAttrs = syn::parse2(attrs_token_stream).unwrap();
+
+            let mut tokens = TokenStream::new();
+            let mut program = backend::ast::Program::default();
+
+            item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+                .unwrap();
+
+            let parsed_struct = &program.state_structs[0];
+
+            assert_eq!(parsed_struct.hash, expected)
+        }
+    }
+
+    #[test]
+    fn struct_with_unknown_hash() {
+        // Mock unknown hash attr
+        let mut attrs_token_stream = TokenStream::new();
+        (quote! {
+            hash = "md5"
+        })
+        .to_tokens(&mut attrs_token_stream);
+
+        // Parse struct and attrs
+        match syn::parse2::<StateAttrs>(attrs_token_stream) {
+            Err(err) => assert_eq!(err.to_string(), "unknown hash function 'md5'"),
+            _ => panic!("unknown hash function should throw an error"),
+        }
+    }
+
     #[test]
     fn struct_with_invalid_codec_format() {
         // Mock no attrs
@@ -325,4 +531,251 @@ mod tests {
 
         assert_eq!(parsed_struct.codec, Codec::DagCbor)
     }
+
+    #[test]
+    fn struct_with_version() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub count: u64
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! { version = 2 }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        assert_eq!(
+            program.state_structs[0]
+                .version
+                .map(|version| version.number),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn no_version_on_raw_state() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub count: u64
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! { codec = "raw", version = 1 }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(diagnostic) => {
+                let res_panic = std::panic::catch_unwind(|| diagnostic.panic());
+                match res_panic {
+                    Err(err) => match err.downcast::<String>() {
+                        Ok(panic_msg_box) => {
+                            assert_eq!(panic_msg_box.as_str(), "#[fvm_state(version = ..)] is only available for states stored with a DAG-CBOR codec.");
+                        }
+                        Err(_) => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            _ => panic!("parse result should be error when a raw state is versioned"),
+        }
+    }
+
+    #[test]
+    fn struct_with_lazy_field() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub count: u64,
+                #[lazy]
+                pub metadata: String
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! { lazy }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        let state = program.state_structs.first().unwrap();
+        assert!(!state.serialized_fields[0].lazy);
+        assert!(state.serialized_fields[1].lazy);
+
+        let ty = &state.serialized_fields[1].ty;
+        assert_eq!(quote!(#ty).to_string(), quote!(String).to_string());
+
+        // The field is wrapped and its marker removed from the emitted structure
+        let emitted: syn::ItemStruct = syn::parse2(tokens).unwrap();
+        let field = emitted.fields.iter().nth(1).unwrap();
+        let ty = &field.ty;
+        assert!(field.attrs.is_empty());
+        assert_eq!(
+            quote!(#ty).to_string(),
+            quote!(fvm_rs_sdk::state::Lazy<String>).to_string()
+        );
+    }
+
+    #[test]
+    fn struct_with_collection_field() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                #[collection]
+                pub balances: Map<u64, u64>,
+                pub registry: Map
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! {}).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        let state = program.state_structs.first().unwrap();
+        assert!(state.serialized_fields[0].collection);
+        assert!(!state.serialized_fields[1].collection);
+
+        // The marker is removed from the emitted structure
+        let emitted: syn::ItemStruct = syn::parse2(tokens).unwrap();
+        assert!(emitted.fields.iter().all(|field| field.attrs.is_empty()));
+    }
+
+    #[test]
+    fn no_non_string_map_key() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub owners: Option<BTreeMap<Address, u64>>
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! {}).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(diagnostic) => {
+                let res_panic = std::panic::catch_unwind(|| diagnostic.panic());
+                match res_panic {
+                    Err(err) => match err.downcast::<String>() {
+                        Ok(panic_msg_box) => {
+                            assert_eq!(panic_msg_box.as_str(), "field 'owners' can not be keyed by 'Address', as the keys of an IPLD map have to be strings.");
+                        }
+                        Err(_) => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            _ => panic!("parse result should be error when a map is keyed by addresses"),
+        }
+    }
+
+    #[test]
+    fn no_lazy_field_on_eager_state() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                #[lazy]
+                pub metadata: Vec<u8>
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! {}).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(diagnostic) => {
+                let res_panic = std::panic::catch_unwind(|| diagnostic.panic());
+                match res_panic {
+                    Err(err) => match err.downcast::<String>() {
+                        Ok(panic_msg_box) => {
+                            assert_eq!(panic_msg_box.as_str(), "field 'metadata' can not be lazy. Use #[fvm_state(lazy)] to store fields in their own block.");
+                        }
+                        Err(_) => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            _ => panic!("parse result should be error when a lazy field is on an eager state"),
+        }
+    }
+
+    #[test]
+    fn no_lazy_state_without_lazy_field() {
+        let item: syn::Item = syn::parse_quote! {
+            pub struct MockStruct {
+                pub count: u64
+            }
+        };
+        let attrs: StateAttrs = syn::parse2(quote! { lazy }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        match item.macro_parse(&mut program, (Some(attrs), &mut tokens)) {
+            Err(diagnostic) => {
+                let res_panic = std::panic::catch_unwind(|| diagnostic.panic());
+                match res_panic {
+                    Err(err) => match err.downcast::<String>() {
+                        Ok(panic_msg_box) => {
+                            assert_eq!(panic_msg_box.as_str(), "#[fvm_state(lazy)] expects fields to store in their own block to be marked with #[lazy].");
+                        }
+                        Err(_) => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            _ => panic!("parse result should be error when a lazy state has no lazy field"),
+        }
+    }
+
+    #[test]
+    fn migration_hook_to_ast() {
+        let item: syn::Item = syn::parse_quote! {
+            pub fn from_v1(previous: MockStructV1) -> MockStruct {
+                MockStruct { count: previous.count, total: 0 }
+            }
+        };
+        let attrs: MigrateAttrs = syn::parse2(quote! { from = 1 }).unwrap();
+
+        let mut tokens = TokenStream::new();
+        let mut program = backend::ast::Program::default();
+
+        item.macro_parse(&mut program, (Some(attrs), &mut tokens))
+            .unwrap();
+
+        let hook = &program.migration_hooks[0];
+        assert_eq!(hook.rust_name.to_string(), "from_v1");
+        assert_eq!(hook.from, 1);
+        assert_eq!(hook.previous.to_token_stream().to_string(), "MockStructV1");
+        assert_eq!(hook.next.to_token_stream().to_string(), "MockStruct");
+    }
+
+    #[test]
+    fn no_migration_hook_without_layouts() {
+        for item in [
+            syn::parse_quote! {
+                pub fn from_v1(&self) -> MockStruct {
+                    unimplemented!()
+                }
+            },
+            syn::parse_quote! {
+                pub fn from_v1(previous: MockStructV1) {}
+            },
+        ] {
+            let item: syn::Item = item;
+            let attrs: MigrateAttrs = syn::parse2(quote! { from = 1 }).unwrap();
+
+            let mut tokens = TokenStream::new();
+            let mut program = backend::ast::Program::default();
+
+            assert!(item
+                .macro_parse(&mut program, (Some(attrs), &mut tokens))
+                .is_err());
+        }
+    }
 }
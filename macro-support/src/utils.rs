@@ -53,6 +53,8 @@ macro_rules! generate_attr_getters {
                         .iter()
                         .filter_map(|a| match &a {
                             $attr_value(value) => Some(value),
+                            #[allow(unreachable_patterns)]
+                            _ => None,
                         })
                         .next()
                 }
@@ -21,6 +21,19 @@ macro_rules! generate_proc_macro {
 generate_proc_macro!(fvm_state, MacroType::State);
 generate_proc_macro!(fvm_actor, MacroType::Actor);
 generate_proc_macro!(fvm_payload, MacroType::Payload);
+generate_proc_macro!(fvm_interface, MacroType::Interface);
+generate_proc_macro!(fvm_event, MacroType::Event);
+generate_proc_macro!(fvm_migrate, MacroType::Migration);
+
+// Called by the macro generated for an interface, along with the implementation of the interface
+#[doc(hidden)]
+#[proc_macro]
+pub fn fvm_actor_interface(input: TokenStream) -> TokenStream {
+    match fvm_rs_sdk_macro_support::expand_interface_implementation(input.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(diagnostic) => (quote! { #diagnostic }).into(),
+    }
+}
 
 #[proc_macro_attribute]
 pub fn fvm_export(_attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -0,0 +1,17 @@
+pub mod counter {
+    use fvm_rs_sdk::actor::{fvm_export, fvm_interface};
+
+    #[fvm_interface]
+    pub trait Counter {
+        #[fvm_export(constructor)]
+        fn new(count: u64) -> Self
+        where
+            Self: Sized;
+
+        #[fvm_export(method_num = 2)]
+        fn read(&self) -> u64;
+
+        #[fvm_export(method_num = 3)]
+        fn add(&mut self, value: u64);
+    }
+}
@@ -0,0 +1,24 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+}
+
+#[fvm_actor]
+impl MockStruct1 {
+    // Test constructor with parameters
+    #[fvm_export(constructor)]
+    pub fn new(count: u64) -> Self {
+        MockStruct1 { count }
+    }
+
+    #[fvm_export(method_num = 2)]
+    pub fn read(&self) -> u64 {
+        self.count
+    }
+}
+
+fn main() {}
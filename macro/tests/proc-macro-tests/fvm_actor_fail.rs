@@ -11,7 +11,7 @@ pub struct MockStruct1 {
 // Fail because of generic
 #[fvm_actor]
 impl<T> MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn add(&mut self, a: T) {
         self.count += T.count
     }
@@ -20,7 +20,7 @@ impl<T> MockStruct1 {
 // Fail because of ref
 #[fvm_actor]
 impl MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn add(&mut self, a: &u64) {
         self.count += a;
     }
@@ -29,7 +29,7 @@ impl MockStruct1 {
 // Fail because of bare function type
 #[fvm_actor]
 impl MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn add(&mut self, a: fn(u64) -> u64) {
         self.count += a
     }
@@ -47,7 +47,7 @@ impl MockStruct1 {
 // Fail because pointer type
 #[fvm_actor]
 impl MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn add(&mut self, a: *mut u64) {
         todo!()
     }
@@ -56,7 +56,7 @@ impl MockStruct1 {
 // Fail because never argument type
 #[fvm_actor]
 impl MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn call_never(&mut self, a: !) {
         todo!()
     }
@@ -65,7 +65,7 @@ impl MockStruct1 {
 // Fail because slice argument type
 #[fvm_actor]
 impl MockStruct1 {
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 2)]
     pub fn call_never(&mut self, a: Box<[u64]>) {
         self.count += a[0]
     }
@@ -89,4 +89,36 @@ impl MockStruct1 {
     }
 }
 
+// Fail because of duplicate method number
+#[fvm_actor]
+impl MockStruct1 {
+    #[fvm_export(method_num = 2)]
+    pub fn add(&mut self, a: u64) {
+        self.count += a
+    }
+
+    #[fvm_export(method_num = 2)]
+    pub fn sub(&mut self, a: u64) {
+        self.count -= a
+    }
+}
+
+// Fail because of reserved method number
+#[fvm_actor]
+impl MockStruct1 {
+    #[fvm_export(method_num = 0)]
+    pub fn add(&mut self, a: u64) {
+        self.count += a
+    }
+}
+
+// Fail because constructor takes self
+#[fvm_actor]
+impl MockStruct1 {
+    #[fvm_export(constructor)]
+    pub fn new(&self) -> Self {
+        MockStruct1 { count: self.count }
+    }
+}
+
 fn main() {}
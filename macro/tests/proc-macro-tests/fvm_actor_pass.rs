@@ -11,7 +11,7 @@ pub struct MockStruct1 {
 #[fvm_rs_sdk::actor::fvm_actor]
 impl MockStruct1 {
     // Test mutable state method & different types
-    #[fvm_export(method_num = 1)]
+    #[fvm_export(method_num = 9)]
     pub fn first_mock(
         &mut self,
         _a: u64,
@@ -38,6 +38,32 @@ impl MockStruct1 {
     pub fn fourth_mock() -> u64 {
         0
     }
+
+    // Test FRC-0042 method name binding
+    #[fvm_export(method_name = "FifthMock")]
+    pub fn fifth_mock(&self) -> u64 {
+        0
+    }
+
+    // Test FRC-0042 method name derived from method identifier
+    #[fvm_export(method_name)]
+    pub fn sixth_mock(&self) -> u64 {
+        0
+    }
+
+    // Test fallible methods
+    #[fvm_export(method_num = 7)]
+    pub fn seventh_mock(&mut self) -> Result<u64, fvm_rs_sdk::actor::ActorError> {
+        Ok(0)
+    }
+    #[fvm_export(method_num = 8)]
+    pub fn eighth_mock() -> Result<(), fvm_rs_sdk::actor::ActorError> {
+        Err(fvm_rs_sdk::actor::ActorError::illegal_argument(
+            "eighth mock",
+        ))
+    }
 }
 
-fn main() {}
+fn main() {
+    let _: &str = ACTOR_INTERFACE;
+}
@@ -0,0 +1,30 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+}
+
+#[fvm_actor(reentrancy = "deny")]
+impl MockStruct1 {
+    // Test constructor and read methods left unlocked
+    #[fvm_export(constructor)]
+    pub fn new() -> Self {
+        MockStruct1 { count: 0 }
+    }
+
+    #[fvm_export(method_num = 2)]
+    pub fn read(&self) -> u64 {
+        self.count
+    }
+
+    // Test write method locked by the actor
+    #[fvm_export(method_num = 3)]
+    pub fn add(&mut self, value: u64) {
+        self.count += value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use fvm_rs_sdk::event::{fvm_event, Event};
+use fvm_rs_sdk::shared::address::Address;
+
+#[fvm_event]
+pub struct MockEvent1 {
+    #[indexed]
+    pub from: Address,
+    pub amount: u64,
+}
+
+// Test event name attribute
+#[fvm_event(name = "mock.renamed")]
+pub struct MockEvent2 {
+    pub count: u64,
+}
+
+fn main() {
+    let event = MockEvent1 {
+        from: Address::new_id(100),
+        amount: 1,
+    }
+    .to_actor_event()
+    .unwrap();
+    assert_eq!(event.entries.len(), 3);
+    assert_eq!(MockEvent2::NAME, "mock.renamed");
+}
@@ -0,0 +1,42 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::shared::address::Address;
+use fvm_rs_sdk::shared::ActorID;
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+    pub owner: ActorID,
+    pub operator: Option<Address>,
+    pub admins: Vec<Address>,
+}
+
+#[fvm_actor]
+impl MockStruct1 {
+    // Test guard on a state field holding an actor id
+    #[fvm_export(method_num = 2, caller = "owner")]
+    pub fn reset(&mut self) {
+        self.count = 0
+    }
+
+    // Test guard on an optional address
+    #[fvm_export(method_num = 3, caller = "operator")]
+    pub fn increment(&mut self) {
+        self.count += 1
+    }
+
+    // Test guard on a list of addresses
+    #[fvm_export(method_num = 4, caller_in = "admins")]
+    pub fn set_owner(&mut self, owner: ActorID) {
+        self.owner = owner
+    }
+
+    // Test guard on the caller type, available without state
+    #[fvm_export(method_num = 5, caller_type = [account, multisig])]
+    pub fn ping() -> u64 {
+        1
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+}
+
+#[fvm_actor]
+impl MockStruct1 {
+    // Test write method locked on its own
+    #[fvm_export(method_num = 2, nonreentrant)]
+    pub fn add(&mut self, value: u64) {
+        self.count += value
+    }
+
+    // Test write method left unlocked
+    #[fvm_export(method_num = 3)]
+    pub fn reset(&mut self) {
+        self.count = 0
+    }
+}
+
+fn main() {}
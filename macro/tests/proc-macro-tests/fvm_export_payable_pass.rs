@@ -0,0 +1,26 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export};
+use fvm_rs_sdk::shared::econ::TokenAmount;
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub deposits: u64,
+}
+
+#[fvm_actor]
+impl MockStruct1 {
+    // Test payable method without reading the value
+    #[fvm_export(method_num = 2, payable)]
+    pub fn fund(&self) {}
+
+    // Test value injected along payload arguments
+    #[fvm_export(method_num = 3, payable)]
+    pub fn deposit(&mut self, times: u64, #[value] value: TokenAmount) {
+        if value > TokenAmount::default() {
+            self.deposits += times;
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::fvm_actor;
+use fvm_rs_sdk::state::*;
+use mock_interface::counter::{Counter, CounterClient};
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+}
+
+// Test implementing an interface declared in another crate
+#[fvm_actor]
+impl Counter for MockStruct1 {
+    fn new(count: u64) -> Self {
+        MockStruct1 { count }
+    }
+
+    fn read(&self) -> u64 {
+        self.count
+    }
+
+    fn add(&mut self, value: u64) {
+        self.count += value
+    }
+}
+
+fn main() {
+    let _: &str = CounterClient::INTERFACE;
+    let _: &str = ACTOR_INTERFACE;
+}
@@ -0,0 +1,53 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export, fvm_interface};
+use fvm_rs_sdk::state::*;
+
+mod interface {
+    use super::*;
+
+    #[fvm_interface]
+    pub trait Counter {
+        #[fvm_export(constructor)]
+        fn new(count: u64) -> Self
+        where
+            Self: Sized;
+
+        #[fvm_export(method_num = 2)]
+        fn read(&self) -> u64;
+
+        // Test default implementation
+        #[fvm_export(method_name)]
+        fn increment(&mut self) {
+            self.add(1)
+        }
+
+        fn add(&mut self, value: u64);
+    }
+}
+
+use interface::Counter;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub count: u64,
+}
+
+#[fvm_actor]
+impl Counter for MockStruct1 {
+    fn new(count: u64) -> Self {
+        MockStruct1 { count }
+    }
+
+    fn read(&self) -> u64 {
+        self.count
+    }
+
+    fn add(&mut self, value: u64) {
+        self.count += value
+    }
+}
+
+fn main() {
+    let _: &str = interface::CounterClient::INTERFACE;
+    let _: &str = ACTOR_INTERFACE;
+}
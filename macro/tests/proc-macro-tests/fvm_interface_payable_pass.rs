@@ -0,0 +1,37 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::actor::{fvm_actor, fvm_export, fvm_interface};
+use fvm_rs_sdk::shared::econ::TokenAmount;
+use fvm_rs_sdk::state::*;
+
+mod interface {
+    use super::*;
+
+    #[fvm_interface]
+    pub trait Vault {
+        // Test value declared on the interface
+        #[fvm_export(method_num = 2, payable)]
+        fn deposit(&mut self, #[value] value: TokenAmount);
+    }
+}
+
+use interface::Vault;
+
+#[fvm_state]
+pub struct MockStruct1 {
+    pub deposits: u64,
+}
+
+#[fvm_actor]
+impl Vault for MockStruct1 {
+    fn deposit(&mut self, value: TokenAmount) {
+        if value > TokenAmount::default() {
+            self.deposits += 1;
+        }
+    }
+}
+
+fn main() {
+    // Clients of payable methods take the value to send, not the injected argument
+    let _: fn(&interface::VaultClient, TokenAmount) -> Result<(), fvm_rs_sdk::actor::ActorError> =
+        interface::VaultClient::deposit;
+}
@@ -0,0 +1,30 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStructV0 {
+    pub count: u64,
+}
+
+#[fvm_state(version = 2)]
+pub struct MockStruct {
+    pub count: u64,
+    pub total: u64,
+}
+
+impl MockStruct {
+    #[fvm_migrate(from = 0)]
+    pub fn from_v0(previous: MockStructV0) -> Self {
+        MockStruct {
+            count: previous.count,
+            total: 0,
+        }
+    }
+}
+
+#[fvm_state(codec = "raw", version = 1)]
+pub struct MockStructRaw {
+    pub count: u64,
+}
+
+fn main() {}
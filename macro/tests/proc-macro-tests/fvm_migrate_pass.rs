@@ -0,0 +1,55 @@
+#![allow(unreachable_code)]
+use fvm_rs_sdk::state::*;
+
+#[fvm_state]
+pub struct MockStructV0 {
+    pub count: u64,
+}
+
+#[fvm_state(version = 1)]
+pub struct MockStructV1 {
+    pub count: u64,
+    pub total: u64,
+}
+
+#[fvm_state(codec = "dag-cbor-map", version = 2)]
+pub struct MockStruct {
+    pub count: u64,
+    pub total: u64,
+    pub owner: u64,
+}
+
+impl MockStruct {
+    #[fvm_migrate(from = 0)]
+    pub fn from_v0(previous: MockStructV0) -> MockStructV1 {
+        MockStructV1 {
+            count: previous.count,
+            total: previous.count,
+        }
+    }
+
+    #[fvm_migrate(from = 1)]
+    pub fn from_v1(previous: MockStructV1) -> Self {
+        MockStruct {
+            count: previous.count,
+            total: previous.total,
+            owner: 0,
+        }
+    }
+}
+
+impl MockStructV1 {
+    #[fvm_migrate(from = 0)]
+    pub fn from_v0(previous: MockStructV0) -> Self {
+        MockStruct::from_v0(previous)
+    }
+}
+
+#[fvm_state(version = 0)]
+pub struct MockStructUnmigrated {
+    pub count: u64,
+}
+
+fn main() {
+    let _: &str = MockStruct::IPLD_SCHEMA;
+}
@@ -11,4 +11,7 @@ pub struct MockStruct2 {
     pub count: u64,
 }
 
-fn main() {}
+fn main() {
+    let _: &str = MockStruct1::PAYLOAD_LAYOUT;
+    let _: &str = MockStruct2::IPLD_SCHEMA;
+}
@@ -0,0 +1,46 @@
+use fvm_rs_sdk::encoding::{to_vec, RawBytes};
+use fvm_rs_sdk::payload::*;
+use fvm_rs_sdk::shared::address::Address;
+use fvm_rs_sdk::shared::bigint::bigint_ser::BigIntDe;
+
+#[fvm_payload]
+pub struct MockBytes {
+    pub list: Vec<u8>,
+    pub array: [u8; 4],
+    #[serde(with = "fvm_rs_sdk::encoding::serde_bytes")]
+    pub bytes: Vec<u8>,
+    pub amount: BigIntDe,
+    pub to: Address,
+    pub params: RawBytes,
+}
+
+// CBOR major types of the encoded fields
+const BYTES: u8 = 2;
+const LIST: u8 = 4;
+
+fn main() {
+    assert_eq!(
+        MockBytes::IPLD_SCHEMA,
+        "type MockBytes struct {\n  list [Int]\n  array [Int]\n  bytes Bytes\n  amount Bytes\n  to Bytes\n  params Bytes\n} representation tuple"
+    );
+
+    // With single byte values, each field is encoded on a known number of bytes after the tuple
+    // header, starting with its major type
+    let encoded = to_vec(&MockBytes {
+        list: vec![1],
+        array: [1, 2, 3, 4],
+        bytes: vec![1],
+        amount: BigIntDe(1.into()),
+        to: Address::new_id(1),
+        params: RawBytes::new(vec![1]),
+    })
+    .unwrap();
+    assert_eq!(encoded[0], 0x86);
+
+    let mut offset = 1;
+    for (major_type, len) in [(LIST, 2), (LIST, 5), (BYTES, 2), (BYTES, 3), (BYTES, 3), (BYTES, 2)] {
+        assert_eq!(encoded[offset] >> 5, major_type);
+        offset += len;
+    }
+    assert_eq!(offset, encoded.len());
+}
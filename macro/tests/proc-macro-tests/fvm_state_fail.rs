@@ -15,4 +15,9 @@ struct InnerStruct {
     pub count: u64,
 }
 
+#[fvm_state]
+pub struct MockStruct3 {
+    pub owners: std::collections::HashMap<u64, u64>,
+}
+
 fn main() {}
@@ -0,0 +1,35 @@
+use fvm_rs_sdk::state::*;
+
+#[fvm_state(lazy)]
+pub struct MockStruct {
+    pub count: u64,
+    #[lazy]
+    pub metadata: String,
+    #[lazy]
+    history: Vec<u64>,
+}
+
+#[fvm_state(codec = "dag-cbor-map", lazy)]
+pub struct MockMapStruct {
+    pub count: u64,
+    #[lazy]
+    pub metadata: String,
+}
+
+#[allow(dead_code)]
+fn update(state: &mut MockStruct) -> &str {
+    let count = state.count;
+    state.history_mut().push(count);
+    state.metadata_mut().push_str(" updated");
+    state.metadata()
+}
+
+fn main() {
+    let _ = MockStruct {
+        count: 0,
+        metadata: Lazy::new(String::from("metadata")),
+        history: Lazy::new(vec![]),
+    };
+
+    let _: &str = MockMapStruct::IPLD_SCHEMA;
+}
@@ -11,4 +11,44 @@ pub struct MockStruct {
     pub count: u64,
 }
 
-fn main() {}
+#[fvm_state(hash = "keccak-256")]
+pub struct MockStructKeccak {
+    pub count: u64,
+}
+
+#[fvm_state(codec = "dag-cbor-map", hash = "sha2-256")]
+pub struct MockStructMap {
+    pub count: u64,
+}
+
+#[fvm_state(codec = "raw")]
+pub struct MockStructRaw {
+    pub count: u64,
+}
+
+impl RawState for MockStructRaw {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.count.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut count = [0u8; 8];
+        count.copy_from_slice(bytes);
+        Ok(MockStructRaw {
+            count: u64::from_be_bytes(count),
+        })
+    }
+}
+
+#[fvm_state]
+pub struct MockStructWithCollections {
+    pub count: u64,
+    #[collection]
+    pub balances: collections::Map<u64, u64>,
+    #[collection]
+    pub orders: collections::Vec<(u64, u64)>,
+}
+
+fn main() {
+    let _: &str = MockStructWithCollections::IPLD_SCHEMA;
+}
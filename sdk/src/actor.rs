@@ -1,2 +0,0 @@
-pub use fvm_rs_sdk_macro::fvm_actor;
-pub use fvm_rs_sdk_macro::fvm_export;
@@ -0,0 +1,39 @@
+//! Context contains the information about the message an entry point is invoked with.
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::{ActorID, MethodNum};
+
+use crate::syscall::{message, network};
+
+/// Context of the message an entry point is invoked with.
+///
+/// Entry points taking a `Context` argument have it filled in by `#[fvm_actor]` rather than
+/// deserialized from the parameters of the message. As its fields are public, tests can build one
+/// to call business logic directly. The origin of the message is not exposed by the syscalls of
+/// the FVM and is therefore not part of the context.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Context {
+    /// Actor calling the entry point
+    pub caller: ActorID,
+    /// Actor the entry point is invoked on
+    pub receiver: ActorID,
+    /// Value sent along the message
+    pub value_received: TokenAmount,
+    /// Current epoch of the chain
+    pub epoch: ChainEpoch,
+    /// Method number the message was sent to
+    pub method_number: MethodNum,
+}
+
+impl Context {
+    /// Read the context of the message being executed
+    pub fn current() -> Self {
+        Context {
+            caller: message::caller(),
+            receiver: message::receiver(),
+            value_received: message::value_received(),
+            epoch: network::curr_epoch(),
+            method_number: message::method_number(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use fvm_shared::error::ExitCode;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("actor error with exit code {exit_code}: {msg}")]
+/// Error returned by an actor's entry point. The execution is aborted with its exit code and message.
+pub struct ActorError {
+    /// Exit code to abort the execution with
+    exit_code: ExitCode,
+    /// Message explaining the error
+    msg: String,
+}
+
+impl ActorError {
+    /// Create a new error with the given exit code and message
+    pub fn new(exit_code: ExitCode, msg: impl Into<String>) -> Self {
+        Self {
+            exit_code,
+            msg: msg.into(),
+        }
+    }
+
+    /// Create a new error with exit code `USR_ILLEGAL_ARGUMENT`
+    pub fn illegal_argument(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_ILLEGAL_ARGUMENT, msg)
+    }
+
+    /// Create a new error with exit code `USR_NOT_FOUND`
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_NOT_FOUND, msg)
+    }
+
+    /// Create a new error with exit code `USR_FORBIDDEN`
+    pub fn forbidden(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_FORBIDDEN, msg)
+    }
+
+    /// Create a new error with exit code `USR_INSUFFICIENT_FUNDS`
+    pub fn insufficient_funds(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_INSUFFICIENT_FUNDS, msg)
+    }
+
+    /// Create a new error with exit code `USR_ILLEGAL_STATE`
+    pub fn illegal_state(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_ILLEGAL_STATE, msg)
+    }
+
+    /// Create a new error with exit code `USR_SERIALIZATION`
+    pub fn serialization(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_SERIALIZATION, msg)
+    }
+
+    /// Create a new error with exit code `USR_UNSPECIFIED`
+    pub fn unspecified(msg: impl Into<String>) -> Self {
+        Self::new(ExitCode::USR_UNSPECIFIED, msg)
+    }
+
+    /// Exit code of the error
+    pub fn exit_code(&self) -> ExitCode {
+        self.exit_code
+    }
+
+    /// Message of the error
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+}
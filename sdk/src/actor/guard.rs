@@ -0,0 +1,53 @@
+//! Guard contains the checks performed on the caller of entry points guarded with `caller`,
+//! `caller_in` or `caller_type`.
+use fvm_shared::actor::builtin::Type;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+
+use crate::syscall::{actor, message};
+
+/// Identity stored in the state that the caller of an entry point can be checked against
+pub trait Caller {
+    /// Returns true if the given actor is the one identified
+    fn is_caller(&self, caller: ActorID) -> bool;
+}
+
+impl Caller for ActorID {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        *self == caller
+    }
+}
+
+impl Caller for Address {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        actor::resolve_address(self) == Some(caller)
+    }
+}
+
+// An unset identity matches no caller
+impl<T: Caller> Caller for Option<T> {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        match self {
+            Some(identity) => identity.is_caller(caller),
+            None => false,
+        }
+    }
+}
+
+impl<T: Caller + ?Sized> Caller for &T {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        (*self).is_caller(caller)
+    }
+}
+
+/// Returns true if the caller of the current message is a builtin actor of one of the given types
+pub fn caller_is_type(types: &[Type]) -> bool {
+    let code_cid = match actor::get_actor_code_cid(&Address::new_id(message::caller())) {
+        Some(code_cid) => code_cid,
+        None => return false,
+    };
+    match actor::get_builtin_actor_type(&code_cid) {
+        Some(caller_type) => types.contains(&caller_type),
+        None => false,
+    }
+}
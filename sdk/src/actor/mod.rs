@@ -0,0 +1,18 @@
+//! Actor contains necessary code to define an actor's interface
+mod context;
+mod error;
+mod guard;
+
+pub use context::Context;
+pub use error::ActorError;
+pub use fvm_rs_sdk_macro::fvm_actor;
+#[doc(hidden)]
+pub use fvm_rs_sdk_macro::fvm_actor_interface;
+pub use fvm_rs_sdk_macro::fvm_export;
+pub use fvm_rs_sdk_macro::fvm_interface;
+pub use guard::{caller_is_type, Caller};
+
+use fvm_shared::ActorID;
+
+/// ID of the init actor, the only actor allowed to call an actor's constructor
+pub const INIT_ACTOR_ID: ActorID = 1;
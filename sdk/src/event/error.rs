@@ -0,0 +1,30 @@
+use fvm_shared::error::{ErrorNumber, ExitCode};
+
+use crate::actor::ActorError;
+
+#[derive(thiserror::Error, Debug)]
+/// Errors related to an actor's events.
+pub enum Error {
+    /// This error is thrown when the value of an entry can not be serialized
+    #[error("failed to serialize entry '{0}': {1:?}")]
+    SerializationFailed(String, fvm_ipld_encoding::Error),
+    /// This error is thrown when the event can not be emitted
+    #[error("failed to emit event: {0:?}")]
+    EmitFailed(ErrorNumber),
+}
+
+impl Error {
+    /// Exit code to abort the execution with when the error occurs
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::SerializationFailed(_, _) => ExitCode::USR_SERIALIZATION,
+            Error::EmitFailed(_) => ExitCode::USR_ILLEGAL_STATE,
+        }
+    }
+}
+
+impl From<Error> for ActorError {
+    fn from(err: Error) -> Self {
+        ActorError::new(err.exit_code(), err.to_string())
+    }
+}
@@ -0,0 +1,78 @@
+//! Event contains the structures that actors emit to publish what happened during their execution,
+//! in the entry format of the FVM actor events.
+pub mod error;
+
+use fvm_ipld_encoding::ser::Serialize;
+use fvm_ipld_encoding::{to_vec, DAG_CBOR};
+
+use crate::event::error::Error;
+pub use fvm_rs_sdk_macro::fvm_event;
+
+/// The entry is not indexed
+pub const FLAG_INDEXED_NONE: u64 = 0b00;
+/// The key of the entry is indexed
+pub const FLAG_INDEXED_KEY: u64 = 0b01;
+/// The value of the entry is indexed
+pub const FLAG_INDEXED_VALUE: u64 = 0b10;
+/// Both the key and the value of the entry are indexed
+pub const FLAG_INDEXED_ALL: u64 = FLAG_INDEXED_KEY | FLAG_INDEXED_VALUE;
+
+/// Key of the entry holding the name of the event
+pub const EVENT_TYPE_KEY: &str = "$type";
+
+/// Entry of an event, holding one of its values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Flags telling which parts of the entry are indexed
+    pub flags: u64,
+    /// Key of the entry
+    pub key: String,
+    /// Codec of the value
+    pub codec: u64,
+    /// Encoded value of the entry
+    pub value: Vec<u8>,
+}
+
+impl Entry {
+    /// Create an entry holding the given value, encoded as DAG-CBOR
+    pub fn new<T: Serialize + ?Sized>(flags: u64, key: &str, value: &T) -> Result<Self, Error> {
+        Ok(Entry {
+            flags,
+            key: key.to_string(),
+            codec: DAG_CBOR,
+            value: to_vec(value).map_err(|err| Error::SerializationFailed(key.to_string(), err))?,
+        })
+    }
+}
+
+/// Event emitted by an actor, as a list of entries
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActorEvent {
+    /// Entries of the event
+    pub entries: Vec<Entry>,
+}
+
+/// Event is a trait to convert a structure to the entries of an event, implemented by `#[fvm_event]`
+pub trait Event {
+    /// Name of the event, published in the entry keyed by `EVENT_TYPE_KEY`
+    const NAME: &'static str;
+
+    /// Convert the structure to the entries of an event
+    fn to_actor_event(&self) -> Result<ActorEvent, Error>;
+}
+
+/// Emit an event from the actor.
+///
+/// The FVM version targeted by the SDK does not expose the syscall to emit events yet. Until it
+/// does, events are dropped on chain, which calling `emit` outside of the `MockRuntime` warns
+/// about at compile time, and are only recorded by the `MockRuntime`.
+#[cfg_attr(
+    not(all(feature = "testing", not(target_arch = "wasm32"))),
+    deprecated(
+        note = "the targeted FVM version has no syscall to emit events, they are dropped on chain"
+    )
+)]
+pub fn emit<E: Event>(event: &E) -> Result<(), Error> {
+    let actor_event = event.to_actor_event()?;
+    crate::syscall::event::emit_event(&actor_event).map_err(Error::EmitFailed)
+}
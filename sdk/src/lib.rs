@@ -1,14 +1,13 @@
 pub mod actor;
+pub mod event;
 pub mod payload;
 pub mod state;
+pub mod syscall;
+pub mod testing;
 
-pub use {fvm_ipld_encoding as encoding, fvm_sdk as syscall, fvm_shared as shared};
+pub use {fvm_ipld_encoding as encoding, fvm_shared as shared};
 
 pub mod cid {
     pub use cid::multihash::Code;
     pub use cid::Cid;
 }
-
-pub mod testing {
-    pub use fvm_sdk::{assert, assert_eq, assert_ne};
-}
@@ -1,7 +1,8 @@
+use std::cell::Cell;
 use std::convert::TryFrom;
 
 use anyhow::{anyhow, Result};
-use cid::multihash::Code;
+use cid::multihash::{Code, MultihashDigest};
 use cid::Cid;
 use fvm_ipld_blockstore::Block;
 
@@ -10,14 +11,99 @@ use crate::state::error::Error::{InvalidCid, MismatchedCid, PutFailed};
 /// A blockstore that delegates to IPLD syscalls.
 pub struct CborBlockstore;
 
-// TODO: Don't hard-code the hash size. Unfortunately, there's no good way to get it from the
-//  codec at the moment.
+/// A blockstore that delegates to IPLD syscalls, hashing the blocks it writes with the hash function
+/// of the state being encoded whatever the one they are put with. Collections store their nodes
+/// through it, as their nodes are always put with Blake2b-256.
+pub struct StateBlockstore;
+
+thread_local! {
+    /// Hash function of the state being encoded, Blake2b-256 outside of `with_hash_code`
+    static HASH_CODE: Cell<Code> = Cell::new(Code::Blake2b256);
+    /// Boolean to know if a modified field was met while encoding a state in `without_flush`,
+    /// `None` outside of it
+    static UNFLUSHED: Cell<Option<bool>> = Cell::new(None);
+}
+
+/// Restores the hash function in use before `with_hash_code`, even if the execution aborts
+struct HashCodeGuard(Code);
+
+impl Drop for HashCodeGuard {
+    fn drop(&mut self) {
+        HASH_CODE.with(|hash_code| hash_code.set(self.0));
+    }
+}
+
+/// Run `f` with the blocks of lazy fields and collections written meanwhile hashed with `code`.
+/// States are encoded through it so that their fields are stored with the hash function of the
+/// state.
+pub fn with_hash_code<T, F>(code: Code, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = HashCodeGuard(HASH_CODE.with(|hash_code| hash_code.replace(code)));
+    f()
+}
+
+/// Hash function of the blocks of lazy fields and collections written now
+pub fn hash_code() -> Code {
+    HASH_CODE.with(|hash_code| hash_code.get())
+}
+
+/// Restores the encoding mode in use before `without_flush`, even if the execution aborts
+struct UnflushedGuard(Option<bool>);
+
+impl Drop for UnflushedGuard {
+    fn drop(&mut self) {
+        UNFLUSHED.with(|unflushed| unflushed.set(self.0));
+    }
+}
+
+/// Run `f` with lazy fields and collections serialized as the CID they were last flushed to,
+/// without writing their blocks, returning whether any of them was modified since
+pub(crate) fn without_flush<T, F>(f: F) -> (T, bool)
+where
+    F: FnOnce() -> T,
+{
+    let _guard = UnflushedGuard(UNFLUSHED.with(|unflushed| unflushed.replace(Some(false))));
+    let result = f();
+    let modified = UNFLUSHED.with(|unflushed| unflushed.get()) == Some(true);
+    (result, modified)
+}
+
+/// Returns `true` if lazy fields and collections are serialized without being flushed, recording
+/// whether the one being serialized was modified
+pub(crate) fn skip_flush(modified: bool) -> bool {
+    UNFLUSHED.with(|unflushed| match unflushed.get() {
+        Some(any_modified) => {
+            unflushed.set(Some(any_modified || modified));
+            true
+        }
+        None => false,
+    })
+}
+
+/// Size of the digest produced by the default hash function, Blake2b-256
+#[deprecated(note = "the digest size depends on the hash function, use `digest_size` instead")]
 pub const SIZE: u32 = 32;
 
+/// Size of the digest produced by a hash function, expected by the FVM when storing a block
+pub fn digest_size(code: Code) -> u32 {
+    match code {
+        Code::Blake2b256 | Code::Sha2_256 | Code::Keccak256 => 32,
+        // Less common hash functions get their size from the digest of empty data
+        _ => code.digest(&[]).size() as u32,
+    }
+}
+
+/// CID of a block, as computed by the FVM when storing it
+pub fn block_cid(code: Code, codec: u64, data: &[u8]) -> Cid {
+    Cid::new_v1(codec, code.digest(data).truncate(digest_size(code) as u8))
+}
+
 impl fvm_ipld_blockstore::Blockstore for CborBlockstore {
     fn get(&self, cid: &Cid) -> Result<Option<Vec<u8>>> {
         // If this fails, the _CID_ is invalid. I.e., we have a bug.
-        fvm_sdk::ipld::get(cid)
+        crate::syscall::ipld::get(cid)
             .map(Some)
             .map_err(|e| InvalidCid(e, *cid).into())
     }
@@ -26,7 +112,7 @@ impl fvm_ipld_blockstore::Blockstore for CborBlockstore {
         let code = Code::try_from(k.hash().code()).map_err(|e| anyhow!(e.to_string()))?;
         let k2 = self.put(code, &Block::new(k.codec(), block))?;
         if k != &k2 {
-            return Err(MismatchedCid(*k, k2).into());
+            return Err(MismatchedCid(Box::new(*k), Box::new(k2)).into());
         }
         Ok(())
     }
@@ -35,8 +121,30 @@ impl fvm_ipld_blockstore::Blockstore for CborBlockstore {
     where
         D: AsRef<[u8]>,
     {
-        let k = fvm_sdk::ipld::put(code.into(), SIZE, block.codec, block.data.as_ref())
-            .map_err(PutFailed)?;
+        let k = crate::syscall::ipld::put(
+            code.into(),
+            digest_size(code),
+            block.codec,
+            block.data.as_ref(),
+        )
+        .map_err(PutFailed)?;
         Ok(k)
     }
 }
+
+impl fvm_ipld_blockstore::Blockstore for StateBlockstore {
+    fn get(&self, cid: &Cid) -> Result<Option<Vec<u8>>> {
+        CborBlockstore.get(cid)
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> Result<()> {
+        CborBlockstore.put_keyed(k, block)
+    }
+
+    fn put<D>(&self, _code: Code, block: &Block<D>) -> Result<Cid>
+    where
+        D: AsRef<[u8]>,
+    {
+        CborBlockstore.put(hash_code(), block)
+    }
+}
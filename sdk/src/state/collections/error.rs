@@ -0,0 +1,21 @@
+use crate::actor::ActorError;
+
+#[derive(thiserror::Error, Debug)]
+/// Errors related to the collections of an actor's state.
+pub enum Error {
+    /// This error is thrown when an operation on the HAMT backing a map fails
+    #[error("failed to access map: {0}")]
+    Hamt(#[from] fvm_ipld_hamt::Error),
+    /// This error is thrown when an operation on the AMT backing a vector fails
+    #[error("failed to access vector: {0}")]
+    Amt(#[from] fvm_ipld_amt::Error),
+    /// This error is thrown when accessing an index past the end of a vector
+    #[error("index {0} is out of bounds for a vector of length {1}")]
+    IndexOutOfBounds(u64, u64),
+}
+
+impl From<Error> for ActorError {
+    fn from(err: Error) -> Self {
+        ActorError::illegal_state(err.to_string())
+    }
+}
@@ -0,0 +1,246 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use cid::Cid;
+use fvm_ipld_encoding::serde::de::DeserializeOwned;
+use fvm_ipld_encoding::serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use fvm_ipld_hamt::{Hamt, Hash};
+
+use crate::state::cbor::{skip_flush, StateBlockstore};
+use crate::state::collections::error::Error;
+
+/// Map is a key-value collection backed by a HAMT stored through the `StateBlockstore`.
+///
+/// Used as a field of a `#[fvm_state]` structure, a map is serialized as a CID link to the root of
+/// its HAMT. Nodes are only loaded when entries are accessed and only the nodes that were modified
+/// are written back when the state is saved.
+pub struct Map<K, V> {
+    /// Root of the HAMT as it was last flushed, if any
+    root: Cell<Option<Cid>>,
+    /// HAMT holding the entries, loaded from the root on first access
+    hamt: RefCell<Option<Hamt<StateBlockstore, V, K>>>,
+    /// Boolean to know if the map was modified since it was last flushed
+    dirty: Cell<bool>,
+}
+
+impl<K, V> Map<K, V>
+where
+    K: Hash + Eq + PartialOrd + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    /// Create a new empty map
+    pub fn new() -> Self {
+        Self {
+            root: Cell::new(None),
+            hamt: RefCell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// Lazily instantiate a map from the CID of its root, nothing is read until an entry is accessed
+    pub fn load(root: &Cid) -> Self {
+        Self {
+            root: Cell::new(Some(*root)),
+            hamt: RefCell::new(None),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Returns a copy of the value associated to a key, if any
+    pub fn get(&self, key: &K) -> Result<Option<V>, Error>
+    where
+        V: Clone,
+    {
+        self.with_hamt(|hamt| Ok(hamt.get(key)?.cloned()))
+    }
+
+    /// Returns `true` if a value is associated to the given key
+    pub fn contains_key(&self, key: &K) -> Result<bool, Error> {
+        self.with_hamt(|hamt| hamt.contains_key(key))
+    }
+
+    /// Associate a value to a key, returning the value previously associated to it if any
+    pub fn set(&mut self, key: K, value: V) -> Result<Option<V>, Error>
+    where
+        V: PartialEq,
+    {
+        self.dirty.set(true);
+        self.with_hamt(|hamt| hamt.set(key, value))
+    }
+
+    /// Remove a key from the map, returning the value that was associated to it if any
+    pub fn delete(&mut self, key: &K) -> Result<Option<V>, Error> {
+        self.dirty.set(true);
+        self.with_hamt(|hamt| Ok(hamt.delete(key)?.map(|(_, value)| value)))
+    }
+
+    /// Returns `true` if the map has no entries
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        self.with_hamt(|hamt| Ok(hamt.is_empty()))
+    }
+
+    /// Iterate over each entry of the map
+    pub fn for_each<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnMut(&K, &V) -> anyhow::Result<()>,
+    {
+        self.with_hamt(|hamt| hamt.for_each(f))
+    }
+
+    /// Write the modified nodes of the map and return the CID of its root, hashed with the hash
+    /// function of the state being encoded
+    pub fn flush(&self) -> Result<Cid, Error> {
+        if let (false, Some(root)) = (self.dirty.get(), self.root.get()) {
+            return Ok(root);
+        }
+
+        let root = self.with_hamt(|hamt| hamt.flush())?;
+        self.root.set(Some(root));
+        self.dirty.set(false);
+
+        Ok(root)
+    }
+
+    // Run an operation on the HAMT, loading it from its root beforehand if needed
+    fn with_hamt<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Hamt<StateBlockstore, V, K>) -> Result<T, fvm_ipld_hamt::Error>,
+    {
+        let mut guard = self.hamt.borrow_mut();
+        let hamt = match guard.take() {
+            Some(hamt) => hamt,
+            None => match self.root.get() {
+                Some(root) => Hamt::load(&root, StateBlockstore)?,
+                None => Hamt::new(StateBlockstore),
+            },
+        };
+
+        Ok(f(guard.insert(hamt))?)
+    }
+}
+
+impl<K, V> Default for Map<K, V>
+where
+    K: Hash + Eq + PartialOrd + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for Map<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("root", &self.root.get())
+            .field("dirty", &self.dirty.get())
+            .finish()
+    }
+}
+
+impl<K, V> Serialize for Map<K, V>
+where
+    K: Hash + Eq + PartialOrd + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Checking if the state is dirty only needs the root the map was last flushed to
+        if skip_flush(self.dirty.get()) {
+            return self.root.get().serialize(serializer);
+        }
+
+        // Modified nodes have to be stored before the map can be referenced by its root
+        let root = self.flush().map_err(ser::Error::custom)?;
+        root.serialize(serializer)
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for Map<K, V>
+where
+    K: Hash + Eq + PartialOrd + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let root = Cid::deserialize(deserializer)?;
+        Ok(Self::load(&root))
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::MockRuntime;
+
+    #[test]
+    fn get_set_delete() {
+        let _rt = MockRuntime::new();
+        let mut map = Map::<u64, String>::new();
+        assert!(map.is_empty().unwrap());
+
+        assert_eq!(map.set(1, "one".to_string()).unwrap(), None);
+        assert_eq!(map.set(2, "two".to_string()).unwrap(), None);
+        assert_eq!(
+            map.set(1, "uno".to_string()).unwrap(),
+            Some("one".to_string())
+        );
+        assert_eq!(map.get(&1).unwrap(), Some("uno".to_string()));
+        assert!(map.contains_key(&2).unwrap());
+
+        assert_eq!(map.delete(&2).unwrap(), Some("two".to_string()));
+        assert_eq!(map.delete(&2).unwrap(), None);
+        assert_eq!(map.get(&2).unwrap(), None);
+        assert!(!map.is_empty().unwrap());
+    }
+
+    #[test]
+    fn root_round_trip() {
+        let _rt = MockRuntime::new();
+        let mut map = Map::<u64, String>::new();
+        for key in 0..100 {
+            map.set(key, key.to_string()).unwrap();
+        }
+        let root = map.flush().unwrap();
+
+        // The map is serialized as a link to its root
+        let bytes = fvm_ipld_encoding::to_vec(&map).unwrap();
+        let map: Map<u64, String> = fvm_ipld_encoding::from_slice(&bytes).unwrap();
+        assert_eq!(map.flush().unwrap(), root);
+
+        let mut count = 0;
+        map.for_each(|key, value| {
+            assert_eq!(&key.to_string(), value);
+            count += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 100);
+    }
+
+    #[test]
+    fn flush_only_when_dirty() {
+        let rt = MockRuntime::new();
+        let mut map = Map::<u64, String>::new();
+        map.set(1, "one".to_string()).unwrap();
+        let root = map.flush().unwrap();
+
+        // Flushing an unmodified map writes nothing, loading it reads nothing either
+        let puts = rt.puts();
+        assert_eq!(map.flush().unwrap(), root);
+        let loaded = Map::<u64, String>::load(&root);
+        assert_eq!(loaded.flush().unwrap(), root);
+        assert!(loaded.hamt.borrow().is_none());
+        assert_eq!(rt.puts(), puts);
+
+        // Modifying the map writes its nodes again under a new root
+        let mut loaded = loaded;
+        loaded.set(2, "two".to_string()).unwrap();
+        assert_ne!(loaded.flush().unwrap(), root);
+        assert!(rt.puts() > puts);
+    }
+}
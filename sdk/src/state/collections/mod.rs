@@ -0,0 +1,10 @@
+//! Collections contains data structures that can be used as fields of a `#[fvm_state]` structure
+//! to store large amounts of data outside of the state object itself.
+mod error;
+mod map;
+mod vec;
+
+pub use error::Error;
+pub use fvm_ipld_hamt::{BytesKey, Hash};
+pub use map::Map;
+pub use vec::Vec;
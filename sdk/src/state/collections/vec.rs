@@ -0,0 +1,254 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use cid::Cid;
+use fvm_ipld_amt::Amt;
+use fvm_ipld_encoding::serde::de::DeserializeOwned;
+use fvm_ipld_encoding::serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::state::cbor::{skip_flush, StateBlockstore};
+use crate::state::collections::error::Error;
+use crate::state::collections::error::Error::IndexOutOfBounds;
+
+/// Vec is an index-addressable collection backed by an AMT stored through the `StateBlockstore`.
+///
+/// Values are kept contiguous: they can be appended with `push` and removed from the end with
+/// `pop`. Used as a field of a `#[fvm_state]` structure, a vector is serialized as a CID link to
+/// the root of its AMT, and only the nodes that were modified are written back when the state is
+/// saved.
+pub struct Vec<V> {
+    /// Root of the AMT as it was last flushed, if any
+    root: Cell<Option<Cid>>,
+    /// AMT holding the values, loaded from the root on first access
+    amt: RefCell<Option<Amt<V, StateBlockstore>>>,
+    /// Boolean to know if the vector was modified since it was last flushed
+    dirty: Cell<bool>,
+}
+
+impl<V> Vec<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    /// Create a new empty vector
+    pub fn new() -> Self {
+        Self {
+            root: Cell::new(None),
+            amt: RefCell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// Lazily instantiate a vector from the CID of its root, nothing is read until a value is
+    /// accessed
+    pub fn load(root: &Cid) -> Self {
+        Self {
+            root: Cell::new(Some(*root)),
+            amt: RefCell::new(None),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Returns the number of values in the vector
+    pub fn len(&self) -> Result<u64, Error> {
+        self.with_amt(|amt| Ok(amt.count()))
+    }
+
+    /// Returns `true` if the vector has no values
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Returns a copy of the value at the given index, if any
+    pub fn get(&self, index: u64) -> Result<Option<V>, Error>
+    where
+        V: Clone,
+    {
+        self.with_amt(|amt| Ok(amt.get(index)?.cloned()))
+    }
+
+    /// Replace the value at the given index, which has to be within the bounds of the vector
+    pub fn set(&mut self, index: u64, value: V) -> Result<(), Error> {
+        let len = self.len()?;
+        if index >= len {
+            return Err(IndexOutOfBounds(index, len));
+        }
+
+        self.dirty.set(true);
+        self.with_amt(|amt| amt.set(index, value))
+    }
+
+    /// Append a value at the end of the vector, returning its index
+    pub fn push(&mut self, value: V) -> Result<u64, Error> {
+        self.dirty.set(true);
+        self.with_amt(|amt| {
+            let index = amt.count();
+            amt.set(index, value)?;
+            Ok(index)
+        })
+    }
+
+    /// Remove the last value of the vector and return it, if any
+    pub fn pop(&mut self) -> Result<Option<V>, Error> {
+        self.dirty.set(true);
+        self.with_amt(|amt| match amt.count() {
+            0 => Ok(None),
+            len => amt.delete(len - 1),
+        })
+    }
+
+    /// Iterate over each value of the vector along with its index
+    pub fn for_each<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnMut(u64, &V) -> anyhow::Result<()>,
+    {
+        self.with_amt(|amt| amt.for_each(f))
+    }
+
+    /// Write the modified nodes of the vector and return the CID of its root, hashed with the hash
+    /// function of the state being encoded
+    pub fn flush(&self) -> Result<Cid, Error> {
+        if let (false, Some(root)) = (self.dirty.get(), self.root.get()) {
+            return Ok(root);
+        }
+
+        let root = self.with_amt(|amt| amt.flush())?;
+        self.root.set(Some(root));
+        self.dirty.set(false);
+
+        Ok(root)
+    }
+
+    // Run an operation on the AMT, loading it from its root beforehand if needed
+    fn with_amt<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Amt<V, StateBlockstore>) -> Result<T, fvm_ipld_amt::Error>,
+    {
+        let mut guard = self.amt.borrow_mut();
+        let amt = match guard.take() {
+            Some(amt) => amt,
+            None => match self.root.get() {
+                Some(root) => Amt::load(&root, StateBlockstore)?,
+                None => Amt::new(StateBlockstore),
+            },
+        };
+
+        Ok(f(guard.insert(amt))?)
+    }
+}
+
+impl<V> Default for Vec<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> fmt::Debug for Vec<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vec")
+            .field("root", &self.root.get())
+            .field("dirty", &self.dirty.get())
+            .finish()
+    }
+}
+
+impl<V> Serialize for Vec<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Checking if the state is dirty only needs the root the vector was last flushed to
+        if skip_flush(self.dirty.get()) {
+            return self.root.get().serialize(serializer);
+        }
+
+        // Modified nodes have to be stored before the vector can be referenced by its root
+        let root = self.flush().map_err(ser::Error::custom)?;
+        root.serialize(serializer)
+    }
+}
+
+impl<'de, V> Deserialize<'de> for Vec<V>
+where
+    V: Serialize + DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let root = Cid::deserialize(deserializer)?;
+        Ok(Self::load(&root))
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::MockRuntime;
+
+    #[test]
+    fn push_pop() {
+        let _rt = MockRuntime::new();
+        let mut vec = Vec::<String>::new();
+        assert!(vec.is_empty().unwrap());
+        assert_eq!(vec.pop().unwrap(), None);
+
+        assert_eq!(vec.push("zero".to_string()).unwrap(), 0);
+        assert_eq!(vec.push("one".to_string()).unwrap(), 1);
+        assert_eq!(vec.len().unwrap(), 2);
+        assert_eq!(vec.get(1).unwrap(), Some("one".to_string()));
+
+        assert_eq!(vec.pop().unwrap(), Some("one".to_string()));
+        assert_eq!(vec.get(1).unwrap(), None);
+        assert_eq!(vec.push("uno".to_string()).unwrap(), 1);
+        assert_eq!(vec.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn set_out_of_bounds() {
+        let _rt = MockRuntime::new();
+        let mut vec = Vec::<String>::new();
+        vec.push("zero".to_string()).unwrap();
+
+        vec.set(0, "nil".to_string()).unwrap();
+        assert_eq!(vec.get(0).unwrap(), Some("nil".to_string()));
+        assert!(matches!(
+            vec.set(1, "one".to_string()),
+            Err(IndexOutOfBounds(1, 1))
+        ));
+        assert_eq!(vec.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn flush_only_dirty_nodes() {
+        let rt = MockRuntime::new();
+        let mut vec = Vec::<u64>::new();
+        for value in 0..100 {
+            vec.push(value).unwrap();
+        }
+        let puts = rt.puts();
+        let root = vec.flush().unwrap();
+        let nodes = rt.puts() - puts;
+
+        // Flushing an unmodified vector writes nothing
+        let puts = rt.puts();
+        let mut vec = Vec::<u64>::load(&root);
+        assert_eq!(vec.flush().unwrap(), root);
+        assert_eq!(rt.puts(), puts);
+
+        // Modifying a value only writes the nodes on its path
+        vec.set(0, 1000).unwrap();
+        assert_ne!(vec.flush().unwrap(), root);
+        assert!(rt.puts() - puts < nodes);
+        assert_eq!(
+            Vec::<u64>::load(&vec.flush().unwrap()).get(0).unwrap(),
+            Some(1000)
+        );
+        assert_eq!(Vec::<u64>::load(&root).get(0).unwrap(), Some(0));
+    }
+}
@@ -1,5 +1,8 @@
 use cid::Cid;
-use fvm_shared::error::ErrorNumber;
+use fvm_sdk::error::NoStateError;
+use fvm_shared::error::{ErrorNumber, ExitCode};
+
+use crate::actor::ActorError;
 
 #[derive(thiserror::Error, Debug)]
 /// Errors related to an actor's state.
@@ -7,10 +10,67 @@ pub enum Error {
     /// This error is thrown when trying to load data from an invalid CID
     #[error("get failed with {0:?} on CID '{1}'")]
     InvalidCid(ErrorNumber, Cid),
-    /// This error is thrown when trying to put a block with mismatched content and CID
+    /// This error is thrown when trying to put a block with mismatched content and CID. The CIDs are
+    /// boxed to keep the error small, as it is returned by every state operation.
     #[error("put block with cid {0} but has cid {1}")]
-    MismatchedCid(Cid, Cid),
+    MismatchedCid(Box<Cid>, Box<Cid>),
     /// This error is thrown when a put fails
     #[error("put failed with {0:?}")]
     PutFailed(ErrorNumber),
+    /// This error is thrown when the actor has no state root, e.g. before it has been constructed,
+    /// or no block is stored under it
+    #[error("state does not exist")]
+    StateNotFound,
+    /// This error is thrown when the state stored under the actor's root can not be read
+    #[error("failed to get state: {0}")]
+    GetStateFailed(anyhow::Error),
+    /// This error is thrown when the state can not be serialized
+    #[error("failed to serialize state: {0:?}")]
+    SerializationFailed(fvm_ipld_encoding::Error),
+    /// This error is thrown when a raw state object can not be converted to bytes
+    #[error("failed to convert state to bytes: {0}")]
+    ToBytesFailed(anyhow::Error),
+    /// This error is thrown when a raw state object can not be converted from the stored bytes
+    #[error("failed to convert state from bytes: {0}")]
+    FromBytesFailed(anyhow::Error),
+    /// This error is thrown when the state was stored with a layout newer than the one of the state
+    /// object
+    #[error("state version {0} is not supported, latest version is {1}")]
+    UnsupportedVersion(u64, u64),
+    /// This error is thrown when the block of a lazy value is not in the state tree
+    #[error("lazy value not found at CID '{0}'")]
+    LazyValueNotFound(Cid),
+    /// This error is thrown when the block of a lazy value can not be read
+    #[error("failed to get lazy value at CID '{1}': {0}")]
+    GetLazyValueFailed(anyhow::Error, Cid),
+    /// This error is thrown when the block of a lazy value can not be stored
+    #[error("failed to store lazy value: {0}")]
+    PutLazyValueFailed(anyhow::Error),
+    /// This error is thrown when a non-reentrant entry point is called while the state is locked by
+    /// another one
+    #[error("re-entrant call to a non-reentrant entry point")]
+    ReentrantCall,
+    /// This error is thrown when the actor's root can not be updated
+    #[error("failed to set root cid: {0}")]
+    SetRootFailed(NoStateError),
+}
+
+impl Error {
+    /// Exit code to abort the execution with when the error occurs
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::PutFailed(_)
+            | Error::SerializationFailed(_)
+            | Error::ToBytesFailed(_)
+            | Error::PutLazyValueFailed(_) => ExitCode::USR_SERIALIZATION,
+            Error::ReentrantCall => ExitCode::USR_FORBIDDEN,
+            _ => ExitCode::USR_ILLEGAL_STATE,
+        }
+    }
+}
+
+impl From<Error> for ActorError {
+    fn from(err: Error) -> Self {
+        ActorError::new(err.exit_code(), err.to_string())
+    }
 }
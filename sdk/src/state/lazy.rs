@@ -0,0 +1,183 @@
+use std::cell::{Cell, OnceCell};
+use std::fmt;
+
+use cid::Cid;
+use fvm_ipld_encoding::serde::de::DeserializeOwned;
+use fvm_ipld_encoding::serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+use fvm_ipld_encoding::CborStore;
+
+use crate::state::cbor::{hash_code, skip_flush, CborBlockstore};
+use crate::state::error::Error;
+
+/// Lazy is a value stored in its own block, linked from the state object holding it.
+///
+/// Fields marked with `#[lazy]` in a `#[fvm_state(lazy)]` structure are wrapped in a `Lazy`, and
+/// serialized as a CID link to their block. The block is only read when the value is first
+/// accessed, and only written back when the state is saved if the value was modified.
+pub struct Lazy<T> {
+    /// CID of the block holding the value as it was last flushed, if any
+    cid: Cell<Option<Cid>>,
+    /// Value, loaded from its block on first access
+    value: OnceCell<T>,
+    /// Boolean to know if the value was modified since it was last flushed
+    dirty: Cell<bool>,
+}
+
+impl<T> Lazy<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Wrap a value that has not been stored yet
+    pub fn new(value: T) -> Self {
+        Self {
+            cid: Cell::new(None),
+            value: OnceCell::from(value),
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// Lazily instantiate a value from the CID of its block, nothing is read until it is accessed
+    pub fn load(cid: &Cid) -> Self {
+        Self {
+            cid: Cell::new(Some(*cid)),
+            value: OnceCell::new(),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Returns the value, reading it from its block on first access
+    pub fn try_get(&self) -> Result<&T, Error> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let cid = match self.cid.get() {
+            Some(cid) => cid,
+            None => unreachable!("a value that was never stored is always set"),
+        };
+        let value = match CborBlockstore.get_cbor::<T>(&cid) {
+            Ok(Some(value)) => value,
+            Ok(None) => return Err(Error::LazyValueNotFound(cid)),
+            Err(err) => return Err(Error::GetLazyValueFailed(err, cid)),
+        };
+
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Returns the value to modify it, reading it from its block on first access
+    pub fn try_get_mut(&mut self) -> Result<&mut T, Error> {
+        self.try_get()?;
+        self.dirty.set(true);
+
+        match self.value.get_mut() {
+            Some(value) => Ok(value),
+            None => unreachable!("the value was loaded above"),
+        }
+    }
+
+    /// Returns the value, aborting the execution if it can not be read
+    pub fn get(&self) -> &T {
+        match self.try_get() {
+            Ok(value) => value,
+            Err(err) => crate::syscall::vm::abort(err.exit_code().value(), Some(&err.to_string())),
+        }
+    }
+
+    /// Returns the value to modify it, aborting the execution if it can not be read
+    pub fn get_mut(&mut self) -> &mut T {
+        match self.try_get_mut() {
+            Ok(value) => value,
+            Err(err) => crate::syscall::vm::abort(err.exit_code().value(), Some(&err.to_string())),
+        }
+    }
+
+    /// Replace the value, without reading the previous one
+    pub fn set(&mut self, value: T) {
+        self.value = OnceCell::from(value);
+        self.dirty.set(true);
+    }
+
+    /// Returns `true` if the value was modified since it was last flushed
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// Write the value if it was modified and return the CID of its block, hashed with the hash
+    /// function of the state being encoded
+    pub fn flush(&self) -> Result<Cid, Error> {
+        if let (false, Some(cid)) = (self.dirty.get(), self.cid.get()) {
+            return Ok(cid);
+        }
+
+        let cid = CborBlockstore
+            .put_cbor(self.try_get()?, hash_code())
+            .map_err(Error::PutLazyValueFailed)?;
+        self.cid.set(Some(cid));
+        self.dirty.set(false);
+
+        Ok(cid)
+    }
+}
+
+impl<T> Clone for Lazy<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cid: self.cid.clone(),
+            value: self.value.clone(),
+            dirty: self.dirty.clone(),
+        }
+    }
+}
+
+impl<T> Default for Lazy<T>
+where
+    T: Default + Serialize + DeserializeOwned,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("cid", &self.cid.get())
+            .field("dirty", &self.dirty.get())
+            .finish()
+    }
+}
+
+impl<T> Serialize for Lazy<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Checking if the state is dirty only needs the CID the value was last flushed to
+        if skip_flush(self.dirty.get()) {
+            return self.cid.get().serialize(serializer);
+        }
+
+        // The value has to be stored before it can be referenced by its CID
+        let cid = self.flush().map_err(ser::Error::custom)?;
+        cid.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lazy<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let cid = Cid::deserialize(deserializer)?;
+        Ok(Self::load(&cid))
+    }
+}
@@ -0,0 +1,110 @@
+//! Lock guards the state of an actor against re-entrant calls to its non-reentrant entry points.
+//! Only actors with non-reentrant entry points load and save their state through this module.
+//!
+//! While a non-reentrant entry point is executed, the state root is replaced by the tuple
+//! `[LOCK_TAG, root]` linking to the state it locks. The state saved meanwhile, e.g. by a
+//! re-entrant call to an entry point that is not non-reentrant, is saved under the lock, which only
+//! the entry point holding it releases once it saved the state. An entry point failing with an
+//! error releases the lock it holds itself, as its caller may handle the error rather than abort,
+//! e.g. when it is called through the generated `dispatch` module. Aborting reverts the lock along
+//! with the rest of the state, on chain as through `MockRuntime::call`.
+
+use cid::multihash::Code;
+use cid::Cid;
+use fvm_ipld_encoding::{from_slice, to_vec, DAG_CBOR};
+
+use crate::state::cbor::digest_size;
+use crate::state::error::Error;
+use crate::state::StateObject;
+use crate::syscall::{ipld, sself};
+
+/// Tag identifying a lock block
+pub const LOCK_TAG: &str = "fvm-rs-sdk/reentrancy-lock";
+
+/// Root of the state locked by a block, if it is a lock
+pub fn locked_root(bytes: &[u8]) -> Option<Cid> {
+    match from_slice::<(String, Cid)>(bytes) {
+        Ok((tag, root)) if tag == LOCK_TAG => Some(root),
+        _ => None,
+    }
+}
+
+/// Root of the actor's state, looking through the lock of the entry point being executed
+pub fn state_root() -> Result<Cid, Error> {
+    let (root, locked) = current_root()?;
+    Ok(locked.unwrap_or(root))
+}
+
+/// Try to load the actor's state, looking through the lock of the entry point being executed.
+/// Actors with non-reentrant entry points load their state with it rather than
+/// `StateObject::try_load`.
+pub fn try_load<S: StateObject>() -> Result<S, Error> {
+    let root = super::state_root()?;
+    let bytes = super::state_block(&root)?;
+    let (root, state) = match locked_root(&bytes) {
+        Some(locked) => (locked, S::try_decode(&super::state_block(&locked)?)?),
+        None => (root, S::try_decode(&bytes)?),
+    };
+    super::set_loaded_root(Some(root));
+    Ok(state)
+}
+
+/// Try to save object as the actor's state, skipping the writes if it is the state last loaded or
+/// saved. While the state is locked, the new state is set under a lock so that it stays held.
+/// Actors with non-reentrant entry points save their state with it rather than
+/// `StateObject::try_save`.
+pub fn try_save<S: StateObject>(state: &S) -> Result<Cid, Error> {
+    let (cid, block) = state.try_encode()?;
+    if super::loaded_root() == Some(cid) {
+        return Ok(cid);
+    }
+
+    // A new actor has no state, and thus no lock
+    let locked = match sself::root() {
+        Ok(_) => current_root()?.1.is_some(),
+        Err(_) => false,
+    };
+
+    let cid = super::put_state_block(&cid, &block)?;
+    let root = match locked {
+        true => put_lock(&cid, S::HASH_CODE)?,
+        false => cid,
+    };
+    sself::set_root(&root).map_err(Error::SetRootFailed)?;
+    super::set_loaded_root(Some(cid));
+    Ok(cid)
+}
+
+/// Lock the actor's state for the execution of a non-reentrant entry point, failing if it is
+/// already locked. The lock block is hashed with `code`, the hash function of the state.
+pub fn lock(code: Code) -> Result<(), Error> {
+    let (root, locked) = current_root()?;
+    if locked.is_some() {
+        return Err(Error::ReentrantCall);
+    }
+
+    let lock = put_lock(&root, code)?;
+    sself::set_root(&lock).map_err(Error::SetRootFailed)
+}
+
+/// Release the lock held by the non-reentrant entry point being executed, setting the root to the
+/// state it locks
+pub fn unlock() -> Result<(), Error> {
+    if let (_, Some(locked)) = current_root()? {
+        sself::set_root(&locked).map_err(Error::SetRootFailed)?;
+    }
+    Ok(())
+}
+
+// Root of the actor's state, along with the root of the state it locks if it is a lock
+fn current_root() -> Result<(Cid, Option<Cid>), Error> {
+    let root = super::state_root()?;
+    let bytes = super::state_block(&root)?;
+    Ok((root, locked_root(&bytes)))
+}
+
+// Write a lock block linking to the given state root
+fn put_lock(root: &Cid, code: Code) -> Result<Cid, Error> {
+    let lock = to_vec(&(LOCK_TAG, root)).map_err(Error::SerializationFailed)?;
+    ipld::put(code.into(), digest_size(code), DAG_CBOR, lock.as_slice()).map_err(Error::PutFailed)
+}
@@ -1,17 +1,134 @@
 //! State contains necessary code to handle a state object in an actor
 pub mod cbor;
-mod error;
+pub mod collections;
+pub mod error;
+mod lazy;
+pub mod lock;
+pub mod version;
 
+use std::cell::Cell;
+
+use cid::multihash::Code;
 use cid::Cid;
+use fvm_shared::error::ErrorNumber;
+
+use crate::state::error::Error;
 pub use fvm_ipld_blockstore::{Block, Blockstore};
 pub use fvm_ipld_encoding::serde;
 pub use fvm_ipld_encoding::tuple::serde_tuple;
-pub use fvm_rs_sdk_macro::fvm_state;
+pub use fvm_rs_sdk_macro::{fvm_migrate, fvm_state};
+pub use lazy::Lazy;
+
+thread_local! {
+    /// Root of the actor's state last loaded or saved, looking through the lock of a non-reentrant
+    /// entry point, so that saving an unchanged state reads nothing
+    static LOADED_ROOT: Cell<Option<Cid>> = const { Cell::new(None) };
+}
 
 /// StateObject is a trait to read and write an actor's state on the Filecoin Virtual Machine
-pub trait StateObject {
-    // Load state object from the FVM state
-    fn load() -> Self;
-    // Save object as an actor's state
-    fn save(&self) -> Cid;
+pub trait StateObject: Sized {
+    // Hash function of the blocks storing the state and its fields
+    const HASH_CODE: Code = Code::Blake2b256;
+
+    // Try to decode state object from the block storing it
+    fn try_decode(bytes: &[u8]) -> Result<Self, Error>;
+    // Try to encode object to the block storing it, along with the CID of the block
+    fn try_encode(&self) -> Result<(Cid, Vec<u8>), Error>;
+
+    // Try to load state object from the FVM state, recording its root
+    fn try_load() -> Result<Self, Error> {
+        let root = state_root()?;
+        let state = Self::try_decode(&state_block(&root)?)?;
+        set_loaded_root(Some(root));
+        Ok(state)
+    }
+
+    // Try to save object as an actor's state, skipping the writes if it is the state last loaded or
+    // saved
+    fn try_save(&self) -> Result<Cid, Error> {
+        let (cid, block) = self.try_encode()?;
+        if loaded_root() == Some(cid) {
+            return Ok(cid);
+        }
+
+        let cid = put_state_block(&cid, &block)?;
+        crate::syscall::sself::set_root(&cid).map_err(Error::SetRootFailed)?;
+        set_loaded_root(Some(cid));
+        Ok(cid)
+    }
+
+    // Check if object differs from the actor's state, looking through the lock of a non-reentrant
+    // entry point. Nothing is written: modified lazy fields and collections make the object dirty,
+    // otherwise its CID is compared to the root of the state last loaded or saved.
+    fn is_dirty(&self) -> Result<bool, Error> {
+        let (encoded, fields_modified) = cbor::without_flush(|| self.try_encode());
+        let (cid, _) = encoded?;
+        if fields_modified {
+            return Ok(true);
+        }
+
+        let root = match loaded_root() {
+            Some(root) => root,
+            None => lock::state_root()?,
+        };
+        Ok(cid != root)
+    }
+
+    // Load state object from the FVM state, aborting the execution on failure
+    fn load() -> Self {
+        match Self::try_load() {
+            Ok(state) => state,
+            Err(err) => crate::syscall::vm::abort(err.exit_code().value(), Some(&err.to_string())),
+        }
+    }
+
+    // Save object as an actor's state, aborting the execution on failure
+    fn save(&self) -> Cid {
+        match self.try_save() {
+            Ok(cid) => cid,
+            Err(err) => crate::syscall::vm::abort(err.exit_code().value(), Some(&err.to_string())),
+        }
+    }
+}
+
+/// RawState is a trait to convert a state object stored with the `raw` codec to and from opaque bytes
+pub trait RawState: Sized {
+    // Convert the state object to the bytes to store
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>>;
+    // Convert stored bytes back to the state object
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self>;
+}
+
+// Root of the actor's state, which a new actor does not have
+fn state_root() -> Result<Cid, Error> {
+    crate::syscall::sself::root().map_err(|_| Error::StateNotFound)
+}
+
+// Block of the actor's state stored under a root
+fn state_block(root: &Cid) -> Result<Vec<u8>, Error> {
+    crate::syscall::ipld::get(root).map_err(|err| match err {
+        ErrorNumber::NotFound => Error::StateNotFound,
+        err => Error::InvalidCid(err, *root),
+    })
+}
+
+// Store the block of an actor's state under its CID
+fn put_state_block(cid: &Cid, block: &[u8]) -> Result<Cid, Error> {
+    crate::syscall::ipld::put(
+        cid.hash().code(),
+        cid.hash().size() as u32,
+        cid.codec(),
+        block,
+    )
+    .map_err(Error::PutFailed)
+}
+
+/// Root of the actor's state last loaded or saved, if any
+pub(crate) fn loaded_root() -> Option<Cid> {
+    LOADED_ROOT.with(|root| root.get())
+}
+
+/// Record the root of the actor's state once loaded or saved, or forget it once reverted
+pub(crate) fn set_loaded_root(root: Option<Cid>) {
+    LOADED_ROOT.with(|loaded| loaded.set(root))
 }
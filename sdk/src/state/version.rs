@@ -0,0 +1,53 @@
+//! Version handles the tag stored along with versioned state objects, so that the layouts they were
+//! stored with can be upgraded when they are loaded.
+//!
+//! A versioned state is stored as the tuple `[VERSION_TAG, version, state]`. States stored before
+//! versioning was adopted carry no tag, and are considered to be of version 0.
+
+use fvm_ipld_encoding::de::{DeserializeOwned, IgnoredAny};
+use fvm_ipld_encoding::ser::Serialize;
+use fvm_ipld_encoding::{from_slice, to_vec, Error};
+
+/// Tag identifying a versioned state block
+pub const VERSION_TAG: &str = "fvm-rs-sdk/state-version";
+
+/// Version of the layout of a stored state, 0 if the state is not tagged
+pub fn stored_version(bytes: &[u8]) -> u64 {
+    match from_slice::<(String, u64, IgnoredAny)>(bytes) {
+        Ok((tag, version, _)) if tag == VERSION_TAG => version,
+        _ => 0,
+    }
+}
+
+/// Decode a stored state, whether it is tagged or not
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    match from_slice::<(String, u64, IgnoredAny)>(bytes) {
+        Ok((tag, _, _)) if tag == VERSION_TAG => {
+            from_slice::<(String, u64, T)>(bytes).map(|(_, _, state)| state)
+        }
+        _ => from_slice(bytes),
+    }
+}
+
+/// Decode a stored state with the layout taken by a migration, then apply the migration
+pub fn migrate<P, N, F>(bytes: &[u8], migration: F) -> Result<N, Error>
+where
+    P: DeserializeOwned,
+    F: FnOnce(P) -> N,
+{
+    decode(bytes).map(migration)
+}
+
+/// Migration is never implemented. It bounds the functions standing in for the migration hooks that
+/// were not declared, so that a versioned state missing one fails to compile with an error naming it.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no migration from version {VERSION}",
+    label = "missing `#[fvm_migrate(from = {VERSION})]`",
+    note = "a state of version {VERSION} is upgraded by a function of an implementation of `{Self}` marked with `#[fvm_migrate(from = {VERSION})]`"
+)]
+pub trait Migration<const VERSION: u64> {}
+
+/// Encode a state along with the version of its layout
+pub fn encode<T: Serialize>(version: u64, state: &T) -> Result<Vec<u8>, Error> {
+    to_vec(&(VERSION_TAG, version, state))
+}
@@ -0,0 +1,21 @@
+//! Syscall gives access to the syscalls of the Filecoin Virtual Machine.
+//!
+//! On non-wasm targets with the `testing` feature, the syscalls used by the generated code are
+//! served by the `MockRuntime` of the current thread so that actors can be tested natively.
+pub use fvm_sdk::*;
+
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub use crate::testing::syscall::{actor, event, ipld, message, network, send, sself, vm};
+
+/// Events are not exposed by the syscalls of the targeted FVM version, they are dropped until they
+/// are.
+#[cfg(not(all(feature = "testing", not(target_arch = "wasm32"))))]
+pub mod event {
+    use crate::event::ActorEvent;
+    use crate::syscall::SyscallResult;
+
+    /// Emit an event, dropped as the targeted FVM version has no syscall to publish it
+    pub fn emit_event(_event: &ActorEvent) -> SyscallResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+//! Testing contains helpers to test actors, such as assertions aborting with a proper exit code
+//! and, with the `testing` feature, a mock runtime to invoke actors natively.
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+mod runtime;
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub(crate) mod syscall;
+
+pub use fvm_sdk::{assert, assert_eq, assert_ne};
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub use runtime::{MockRuntime, SentMessage};
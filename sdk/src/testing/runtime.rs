@@ -0,0 +1,291 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use cid::multihash::{Code, MultihashDigest};
+use cid::Cid;
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_ipld_encoding::{RawBytes, DAG_CBOR};
+use fvm_shared::actor::builtin::Type;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::receipt::Receipt;
+use fvm_shared::sys::{BlockId, Codec};
+use fvm_shared::{ActorID, MethodNum, IPLD_RAW};
+
+use crate::actor::ActorError;
+use crate::event::ActorEvent;
+use crate::syscall::NO_DATA_BLOCK_ID;
+
+thread_local! {
+    // Runtime serving the syscalls of the current thread
+    static RUNTIME: RefCell<Option<Rc<RefCell<Runtime>>>> = const { RefCell::new(None) };
+}
+
+/// Message sent by an actor through the `send` syscall
+#[derive(Clone, Debug, PartialEq)]
+pub struct SentMessage {
+    /// Address of the recipient
+    pub to: Address,
+    /// Method invoked on the recipient
+    pub method: MethodNum,
+    /// Parameters of the message
+    pub params: RawBytes,
+    /// Value transferred with the message
+    pub value: TokenAmount,
+}
+
+/// Payload of the unwinding triggered by an abort, caught by `MockRuntime::call`
+pub(crate) struct Abort;
+
+/// Part of the runtime reverted when a call aborts
+pub(crate) struct Snapshot {
+    root: Option<Cid>,
+    sends: Vec<SentMessage>,
+    events: Vec<ActorEvent>,
+}
+
+/// Execution context and storage backing the mocked syscalls
+pub(crate) struct Runtime {
+    pub(crate) caller: ActorID,
+    pub(crate) receiver: ActorID,
+    pub(crate) method_number: MethodNum,
+    pub(crate) value_received: TokenAmount,
+    pub(crate) balance: TokenAmount,
+    pub(crate) epoch: ChainEpoch,
+    pub(crate) root: Option<Cid>,
+    pub(crate) blockstore: MemoryBlockstore,
+    pub(crate) puts: usize,
+    pub(crate) blocks: Vec<(Codec, Vec<u8>)>,
+    pub(crate) addresses: HashMap<Address, ActorID>,
+    pub(crate) actor_codes: HashMap<ActorID, Cid>,
+    pub(crate) builtin_types: HashMap<Cid, Type>,
+    pub(crate) sends: Vec<SentMessage>,
+    pub(crate) receipts: VecDeque<Receipt>,
+    pub(crate) events: Vec<ActorEvent>,
+    pub(crate) abort: Option<ActorError>,
+}
+
+impl Runtime {
+    /// Register a block, returning its id. Ids start at 1 as 0 is `NO_DATA_BLOCK_ID`.
+    pub(crate) fn put_block(&mut self, codec: Codec, data: &[u8]) -> BlockId {
+        self.blocks.push((codec, data.to_vec()));
+        self.blocks.len() as BlockId
+    }
+
+    /// Take a snapshot of the changes an actor can make during a call
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            root: self.root,
+            sends: self.sends.clone(),
+            events: self.events.clone(),
+        }
+    }
+
+    /// Revert the changes made since the snapshot was taken. Stored blocks are kept, as they are
+    /// content addressed and unreachable once the root is reverted, while the root of the state
+    /// last loaded or saved is forgotten.
+    pub(crate) fn revert(&mut self, snapshot: Snapshot) {
+        self.root = snapshot.root;
+        crate::state::set_loaded_root(None);
+        self.sends = snapshot.sends;
+        self.events = snapshot.events;
+    }
+
+    /// Get a registered block by its id
+    pub(crate) fn get_block(&self, id: BlockId) -> Option<&(Codec, Vec<u8>)> {
+        match id {
+            NO_DATA_BLOCK_ID => None,
+            id => self.blocks.get(id as usize - 1),
+        }
+    }
+}
+
+/// Run an operation on the runtime installed on the current thread
+pub(crate) fn with_runtime<T>(f: impl FnOnce(&mut Runtime) -> T) -> T {
+    let runtime = RUNTIME.with(|runtime| runtime.borrow().clone());
+    match runtime {
+        Some(runtime) => f(&mut runtime.borrow_mut()),
+        None => {
+            panic!("syscall used outside of a MockRuntime, create one before invoking the actor")
+        }
+    }
+}
+
+/// MockRuntime serves the syscalls of the current thread from memory, to invoke actors in native
+/// unit tests.
+///
+/// The runtime is installed on the current thread when created, and removed when dropped.
+pub struct MockRuntime {
+    runtime: Rc<RefCell<Runtime>>,
+}
+
+impl MockRuntime {
+    /// Create a new runtime and install it on the current thread
+    pub fn new() -> Self {
+        let runtime = Rc::new(RefCell::new(Runtime {
+            caller: 0,
+            receiver: 0,
+            method_number: 0,
+            value_received: TokenAmount::default(),
+            balance: TokenAmount::default(),
+            epoch: 0,
+            root: None,
+            blockstore: MemoryBlockstore::default(),
+            puts: 0,
+            blocks: Vec::new(),
+            addresses: HashMap::new(),
+            actor_codes: HashMap::new(),
+            builtin_types: HashMap::new(),
+            sends: Vec::new(),
+            receipts: VecDeque::new(),
+            events: Vec::new(),
+            abort: None,
+        }));
+        RUNTIME.with(|installed| *installed.borrow_mut() = Some(runtime.clone()));
+        // The root of a state loaded from a previous runtime is not the one of this runtime
+        crate::state::set_loaded_root(None);
+
+        MockRuntime { runtime }
+    }
+
+    /// Set the actor calling the actor under test
+    pub fn set_caller(&self, caller: ActorID) {
+        self.runtime.borrow_mut().caller = caller;
+    }
+
+    /// Set the id of the actor under test
+    pub fn set_receiver(&self, receiver: ActorID) {
+        self.runtime.borrow_mut().receiver = receiver;
+    }
+
+    /// Set the value received with the next calls
+    pub fn set_value_received(&self, value: TokenAmount) {
+        self.runtime.borrow_mut().value_received = value;
+    }
+
+    /// Set the balance of the actor under test
+    pub fn set_balance(&self, balance: TokenAmount) {
+        self.runtime.borrow_mut().balance = balance;
+    }
+
+    /// Set the current epoch of the chain
+    pub fn set_epoch(&self, epoch: ChainEpoch) {
+        self.runtime.borrow_mut().epoch = epoch;
+    }
+
+    /// Make an address resolvable to the given actor id
+    pub fn set_address(&self, address: Address, id: ActorID) {
+        self.runtime.borrow_mut().addresses.insert(address, id);
+    }
+
+    /// Make an actor a builtin actor of the given type, as checked by `caller_type` guards
+    pub fn set_actor_type(&self, id: ActorID, actor_type: Type) {
+        // Mocked code CIDs only have to be distinct for each type
+        let code_cid = Cid::new_v1(
+            IPLD_RAW,
+            Code::Blake2b256.digest(format!("fil/mock/{:?}", actor_type).as_bytes()),
+        );
+        let mut runtime = self.runtime.borrow_mut();
+        runtime.actor_codes.insert(id, code_cid);
+        runtime.builtin_types.insert(code_cid, actor_type);
+    }
+
+    /// Queue the receipt returned by the next `send` syscall. Without any queued receipt, sends
+    /// succeed with no return data.
+    pub fn push_send_receipt(&self, receipt: Receipt) {
+        self.runtime.borrow_mut().receipts.push_back(receipt);
+    }
+
+    /// Returns the current root of the actor's state, if any
+    pub fn root(&self) -> Option<Cid> {
+        self.runtime.borrow().root
+    }
+
+    /// Returns the number of blocks stored by the actor so far, counting every `put` syscall even
+    /// if the block was already stored
+    pub fn puts(&self) -> usize {
+        self.runtime.borrow().puts
+    }
+
+    /// Returns the messages sent by the actor so far
+    pub fn sends(&self) -> Vec<SentMessage> {
+        self.runtime.borrow().sends.clone()
+    }
+
+    /// Returns the events emitted by the actor so far
+    pub fn events(&self) -> Vec<ActorEvent> {
+        self.runtime.borrow().events.clone()
+    }
+
+    /// Returns the error of the last call that aborted, if any
+    pub fn last_abort(&self) -> Option<ActorError> {
+        self.runtime.borrow().abort.clone()
+    }
+
+    /// Invoke an actor's entry point by method number, returning its return data or the error it
+    /// aborted with. Empty parameters are passed as `NO_DATA_BLOCK_ID`.
+    ///
+    /// As on chain, an abort reverts the state root, the events emitted and the messages sent
+    /// during the call.
+    pub fn call(
+        &self,
+        invoke: fn(u32) -> u32,
+        method: MethodNum,
+        params: &RawBytes,
+    ) -> Result<Option<RawBytes>, ActorError> {
+        let (params_id, snapshot) = {
+            let mut runtime = self.runtime.borrow_mut();
+            runtime.method_number = method;
+            runtime.abort = None;
+            let params_id = match params.len() {
+                0 => NO_DATA_BLOCK_ID,
+                _ => runtime.put_block(DAG_CBOR, params.bytes()),
+            };
+            (params_id, runtime.snapshot())
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| invoke(params_id))) {
+            Ok(NO_DATA_BLOCK_ID) => Ok(None),
+            Ok(return_id) => match self.runtime.borrow().get_block(return_id) {
+                Some((_, data)) => Ok(Some(RawBytes::new(data.clone()))),
+                None => Err(ActorError::new(
+                    ExitCode::USR_UNSPECIFIED,
+                    format!("invalid return block id {}", return_id),
+                )),
+            },
+            Err(payload) => {
+                if !payload.is::<Abort>() {
+                    panic::resume_unwind(payload);
+                }
+                self.runtime.borrow_mut().revert(snapshot);
+                match self.last_abort() {
+                    Some(err) => Err(err),
+                    None => unreachable!("an abort is always recorded before unwinding"),
+                }
+            }
+        }
+    }
+}
+
+impl Default for MockRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MockRuntime {
+    fn drop(&mut self) {
+        RUNTIME.with(|installed| {
+            let mut installed = installed.borrow_mut();
+            if let Some(runtime) = installed.as_ref() {
+                if Rc::ptr_eq(runtime, &self.runtime) {
+                    *installed = None;
+                }
+            }
+        });
+    }
+}
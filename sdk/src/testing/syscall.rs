@@ -0,0 +1,220 @@
+//! Mocked syscalls, mirroring the signatures of `fvm_sdk` and served by the `MockRuntime`.
+
+pub mod actor {
+    use cid::Cid;
+    use fvm_shared::actor::builtin::Type;
+    use fvm_shared::address::Address;
+    use fvm_shared::ActorID;
+
+    use crate::testing::runtime::with_runtime;
+
+    /// Resolves an address to the id registered with `MockRuntime::set_address`, ID addresses
+    /// resolve to themselves
+    pub fn resolve_address(addr: &Address) -> Option<ActorID> {
+        if let Ok(id) = addr.id() {
+            return Some(id);
+        }
+        with_runtime(|runtime| runtime.addresses.get(addr).copied())
+    }
+
+    /// Returns the code CID of an actor registered with `MockRuntime::set_actor_type`
+    pub fn get_actor_code_cid(addr: &Address) -> Option<Cid> {
+        let id = resolve_address(addr)?;
+        with_runtime(|runtime| runtime.actor_codes.get(&id).copied())
+    }
+
+    /// Returns the builtin actor type of a code CID registered with `MockRuntime::set_actor_type`
+    pub fn get_builtin_actor_type(code_cid: &Cid) -> Option<Type> {
+        with_runtime(|runtime| runtime.builtin_types.get(code_cid).copied())
+    }
+}
+
+pub mod event {
+    use crate::event::ActorEvent;
+    use crate::syscall::SyscallResult;
+    use crate::testing::runtime::with_runtime;
+
+    /// Record the emitted event, returned by `MockRuntime::events`
+    pub fn emit_event(event: &ActorEvent) -> SyscallResult<()> {
+        with_runtime(|runtime| runtime.events.push(event.clone()));
+        Ok(())
+    }
+}
+
+pub mod ipld {
+    use std::convert::TryFrom;
+
+    use cid::multihash::{Code, MultihashDigest};
+    use cid::Cid;
+    use fvm_ipld_blockstore::Blockstore;
+    use fvm_shared::error::ErrorNumber;
+    use fvm_shared::sys::{BlockId, Codec};
+
+    use crate::syscall::SyscallResult;
+    use crate::testing::runtime::with_runtime;
+
+    /// Store a block in the in-memory blockstore and return its CID
+    pub fn put(mh_code: u64, mh_size: u32, codec: u64, data: &[u8]) -> SyscallResult<Cid> {
+        let code = Code::try_from(mh_code).map_err(|_| ErrorNumber::IllegalArgument)?;
+        let digest = code.digest(data);
+        if mh_size > digest.size() as u32 {
+            return Err(ErrorNumber::IllegalArgument);
+        }
+        let cid = Cid::new_v1(codec, digest.truncate(mh_size as u8));
+
+        with_runtime(|runtime| {
+            runtime.puts += 1;
+            runtime.blockstore.put_keyed(&cid, data)
+        })
+        .map_err(|_| ErrorNumber::IllegalArgument)?;
+        Ok(cid)
+    }
+
+    /// Get a block from the in-memory blockstore
+    pub fn get(cid: &Cid) -> SyscallResult<Vec<u8>> {
+        match with_runtime(|runtime| runtime.blockstore.get(cid)) {
+            Ok(Some(data)) => Ok(data),
+            _ => Err(ErrorNumber::NotFound),
+        }
+    }
+
+    /// Register a block and return its id
+    pub fn put_block(codec: Codec, data: &[u8]) -> SyscallResult<BlockId> {
+        Ok(with_runtime(|runtime| runtime.put_block(codec, data)))
+    }
+
+    /// Get a registered block by its id
+    pub fn get_block(id: BlockId, _size_hint: Option<u32>) -> SyscallResult<Vec<u8>> {
+        with_runtime(|runtime| match runtime.get_block(id) {
+            Some((_, data)) => Ok(data.clone()),
+            None => Err(ErrorNumber::InvalidHandle),
+        })
+    }
+}
+
+pub mod message {
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ErrorNumber;
+    use fvm_shared::sys::{BlockId, Codec};
+    use fvm_shared::{ActorID, MethodNum};
+
+    use crate::syscall::SyscallResult;
+    use crate::testing::runtime::with_runtime;
+
+    /// Returns the caller set with `MockRuntime::set_caller`
+    pub fn caller() -> ActorID {
+        with_runtime(|runtime| runtime.caller)
+    }
+
+    /// Returns the receiver set with `MockRuntime::set_receiver`
+    pub fn receiver() -> ActorID {
+        with_runtime(|runtime| runtime.receiver)
+    }
+
+    /// Returns the method number passed to `MockRuntime::call`
+    pub fn method_number() -> MethodNum {
+        with_runtime(|runtime| runtime.method_number)
+    }
+
+    /// Returns the value set with `MockRuntime::set_value_received`
+    pub fn value_received() -> TokenAmount {
+        with_runtime(|runtime| runtime.value_received.clone())
+    }
+
+    /// Returns the parameters passed to `MockRuntime::call`
+    pub fn params_raw(id: BlockId) -> SyscallResult<(Codec, Vec<u8>)> {
+        with_runtime(|runtime| match runtime.get_block(id) {
+            Some(block) => Ok(block.clone()),
+            None => Err(ErrorNumber::InvalidHandle),
+        })
+    }
+}
+
+pub mod network {
+    use fvm_shared::clock::ChainEpoch;
+
+    use crate::testing::runtime::with_runtime;
+
+    /// Returns the epoch set with `MockRuntime::set_epoch`
+    pub fn curr_epoch() -> ChainEpoch {
+        with_runtime(|runtime| runtime.epoch)
+    }
+}
+
+pub mod send {
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::address::Address;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::receipt::Receipt;
+    use fvm_shared::MethodNum;
+
+    use crate::syscall::SyscallResult;
+    use crate::testing::runtime::with_runtime;
+    use crate::testing::SentMessage;
+
+    /// Record the sent message and return the next receipt queued with
+    /// `MockRuntime::push_send_receipt`
+    pub fn send(
+        to: &Address,
+        method: MethodNum,
+        params: RawBytes,
+        value: TokenAmount,
+    ) -> SyscallResult<Receipt> {
+        with_runtime(|runtime| {
+            runtime.sends.push(SentMessage {
+                to: *to,
+                method,
+                params,
+                value,
+            });
+            Ok(runtime.receipts.pop_front().unwrap_or(Receipt {
+                exit_code: ExitCode::OK,
+                return_data: RawBytes::default(),
+                gas_used: 0,
+            }))
+        })
+    }
+}
+
+pub mod sself {
+    use cid::Cid;
+    use fvm_shared::econ::TokenAmount;
+
+    use crate::syscall::error::NoStateError;
+    use crate::testing::runtime::with_runtime;
+
+    /// Returns the root of the actor's state
+    pub fn root() -> Result<Cid, NoStateError> {
+        with_runtime(|runtime| runtime.root.ok_or(NoStateError))
+    }
+
+    /// Set the root of the actor's state
+    pub fn set_root(cid: &Cid) -> Result<(), NoStateError> {
+        with_runtime(|runtime| runtime.root = Some(*cid));
+        Ok(())
+    }
+
+    /// Returns the balance set with `MockRuntime::set_balance`
+    pub fn current_balance() -> TokenAmount {
+        with_runtime(|runtime| runtime.balance.clone())
+    }
+}
+
+pub mod vm {
+    use fvm_shared::error::ExitCode;
+
+    use crate::actor::ActorError;
+    use crate::testing::runtime::{with_runtime, Abort};
+
+    /// Record the abort and unwind back to `MockRuntime::call`
+    pub fn abort(code: u32, message: Option<&str>) -> ! {
+        with_runtime(|runtime| {
+            runtime.abort = Some(ActorError::new(
+                ExitCode::new(code),
+                message.unwrap_or_default(),
+            ))
+        });
+        std::panic::resume_unwind(Box::new(Abort))
+    }
+}